@@ -1,5 +1,4 @@
 use bbqueue::BBBuffer;
-use bno08x_rvc;
 use std::borrow::Borrow;
 
 static BB: BBBuffer<{ bno08x_rvc::BUFFER_SIZE }> = BBBuffer::new();
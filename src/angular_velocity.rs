@@ -0,0 +1,119 @@
+use crate::parser::Bno08xRvcPrettyFrame;
+use crate::quaternion::Quaternion;
+use crate::resample::TimestampedFrame;
+use crate::BNO08X_UART_RVC_FRAME_RATE_HZ;
+
+/// Body-frame angular velocity, rad/s.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AngularVelocity {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl AngularVelocity {
+    pub fn as_array(&self) -> [f32; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    pub fn degrees_per_second(&self) -> [f32; 3] {
+        [
+            self.x.to_degrees(),
+            self.y.to_degrees(),
+            self.z.to_degrees(),
+        ]
+    }
+
+    pub fn magnitude(&self) -> f32 {
+        libm::sqrtf(self.x * self.x + self.y * self.y + self.z * self.z)
+    }
+}
+
+/// Derives angular rate from consecutive orientations.
+///
+/// The rotation between two frames is taken as `q_prev⁻¹ * q_now` along
+/// the shortest arc, so yaw wrapping at ±180° is harmless. The time step
+/// comes from the timestamps or, for plain frames, from the index
+/// difference at 10 ms per count, so dropped frames stretch the step
+/// instead of inflating the rate. Gaps longer than `max_gap_frames`
+/// restart the estimate, as the rotation between them is ambiguous.
+pub struct AngularVelocityEstimator {
+    alpha: f32,
+    max_gap_frames: u8,
+    last: Option<(Quaternion, u8, Option<u64>)>,
+    estimate: Option<AngularVelocity>,
+}
+
+impl AngularVelocityEstimator {
+    /// `smoothing` is the exponential smoothing factor in `(0, 1]`, 1 for
+    /// no smoothing.
+    pub fn new(smoothing: f32, max_gap_frames: u8) -> Self {
+        AngularVelocityEstimator {
+            alpha: smoothing.clamp(f32::EPSILON, 1.0),
+            max_gap_frames,
+            last: None,
+            estimate: None,
+        }
+    }
+
+    pub fn update(&mut self, frame: &Bno08xRvcPrettyFrame) -> Option<AngularVelocity> {
+        self.step(frame, None)
+    }
+
+    pub fn update_timestamped(&mut self, frame: &TimestampedFrame) -> Option<AngularVelocity> {
+        self.step(&frame.frame, Some(frame.timestamp_us))
+    }
+
+    pub fn estimate(&self) -> Option<AngularVelocity> {
+        self.estimate
+    }
+
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.estimate = None;
+    }
+
+    fn step(
+        &mut self,
+        frame: &Bno08xRvcPrettyFrame,
+        timestamp_us: Option<u64>,
+    ) -> Option<AngularVelocity> {
+        let q = frame.orientation();
+        let last = self.last.replace((q, frame.index, timestamp_us));
+        let (q_prev, index_prev, ts_prev) = last?;
+
+        let frames = frame.index.wrapping_sub(index_prev);
+        if frames == 0 || frames > self.max_gap_frames {
+            self.estimate = None;
+            return None;
+        }
+        let dt = match (timestamp_us, ts_prev) {
+            (Some(now), Some(prev)) if now > prev => (now - prev) as f32 / 1e6,
+            _ => frames as f32 / BNO08X_UART_RVC_FRAME_RATE_HZ as f32,
+        };
+
+        let v = (q_prev.conjugate() * q).to_rotation_vector();
+        let raw = AngularVelocity {
+            x: v[0] / dt,
+            y: v[1] / dt,
+            z: v[2] / dt,
+        };
+        let smoothed = match self.estimate {
+            None => raw,
+            Some(prev) => AngularVelocity {
+                x: prev.x + self.alpha * (raw.x - prev.x),
+                y: prev.y + self.alpha * (raw.y - prev.y),
+                z: prev.z + self.alpha * (raw.z - prev.z),
+            },
+        };
+        self.estimate = Some(smoothed);
+        self.estimate
+    }
+}
+
+impl Default for AngularVelocityEstimator {
+    fn default() -> Self {
+        Self::new(1.0, 25)
+    }
+}
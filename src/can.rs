@@ -0,0 +1,254 @@
+//! Classic CAN packing of RVC samples, see `bno08x_rvc.dbc`.
+//!
+//! Each sample is sent as two 8-byte frames sharing a 4-bit rolling
+//! counter:
+//!
+//! | frame        | bytes 0-1      | bytes 2-3        | bytes 4-5       | byte 6                              | byte 7   |
+//! |--------------|----------------|------------------|-----------------|-------------------------------------|----------|
+//! | orientation  | yaw (0.01°)    | pitch (0.01°)    | roll (0.01°)    | counter (0-3), motion intent (4-7)  | checksum |
+//! | acceleration | x (mg)         | y (mg)           | z (mg)          | counter (0-3), motion request (4-7) | checksum |
+//!
+//! Values are little-endian `i16`, straight from the raw frame, so no
+//! floating point is needed. The checksum is the bitwise inverse of the
+//! 8-bit sum of the identifier bytes and data bytes 0 to 6.
+
+use embedded_can::{ExtendedId, Frame, Id, StandardId};
+
+use crate::parser::Bno08xRvcRawFrame;
+
+pub const CAN_ORIENTATION_ID: u16 = 0x510;
+pub const CAN_ACCELERATION_ID: u16 = 0x511;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CanError {
+    UnexpectedId,
+    BadLength,
+    BadChecksum,
+    /// An acceleration frame whose counter does not match the pending
+    /// orientation frame, or with no orientation frame before it.
+    CounterMismatch,
+    /// The `Frame` type refused to build a frame, e.g. an extended id on a
+    /// standard-only controller.
+    FrameRejected,
+}
+
+/// Minimal classic CAN data frame, for use without a HAL frame type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CanFrame {
+    pub id: Id,
+    pub dlc: u8,
+    pub data: [u8; 8],
+}
+
+impl Frame for CanFrame {
+    fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+        if data.len() > 8 {
+            return None;
+        }
+        let mut frame = CanFrame {
+            id: id.into(),
+            dlc: data.len() as u8,
+            data: [0; 8],
+        };
+        frame.data[..data.len()].copy_from_slice(data);
+        Some(frame)
+    }
+
+    fn new_remote(_id: impl Into<Id>, _dlc: usize) -> Option<Self> {
+        None
+    }
+
+    fn is_extended(&self) -> bool {
+        matches!(self.id, Id::Extended(_))
+    }
+
+    fn is_remote_frame(&self) -> bool {
+        false
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn dlc(&self) -> usize {
+        self.dlc as usize
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data[..self.dlc as usize]
+    }
+}
+
+fn raw_id(id: Id) -> u32 {
+    match id {
+        Id::Standard(id) => id.as_raw() as u32,
+        Id::Extended(id) => id.as_raw(),
+    }
+}
+
+pub fn can_checksum(id: Id, data: &[u8]) -> u8 {
+    let sum = raw_id(id)
+        .to_le_bytes()
+        .iter()
+        .chain(data.iter())
+        .fold(0u8, |sum, b| sum.wrapping_add(*b));
+    !sum
+}
+
+fn pack(id: Id, values: [i16; 3], counter: u8, nibble: u8) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    for (chunk, value) in data.chunks_exact_mut(2).zip(values.iter()) {
+        chunk.copy_from_slice(&value.to_le_bytes());
+    }
+    data[6] = (counter & 0x0F) | (nibble << 4);
+    data[7] = can_checksum(id, &data[..7]);
+    data
+}
+
+fn unpack(id: Id, data: &[u8]) -> Result<([i16; 3], u8, u8), CanError> {
+    if data.len() != 8 {
+        return Err(CanError::BadLength);
+    }
+    if can_checksum(id, &data[..7]) != data[7] {
+        return Err(CanError::BadChecksum);
+    }
+    let value = |i: usize| i16::from_le_bytes([data[i], data[i + 1]]);
+    Ok(([value(0), value(2), value(4)], data[6] & 0x0F, data[6] >> 4))
+}
+
+/// Packs raw frames into orientation / acceleration CAN frames.
+pub struct CanEncoder {
+    orientation_id: Id,
+    acceleration_id: Id,
+    counter: u8,
+}
+
+impl Default for CanEncoder {
+    fn default() -> Self {
+        CanEncoder::new(
+            StandardId::new(CAN_ORIENTATION_ID).unwrap().into(),
+            StandardId::new(CAN_ACCELERATION_ID).unwrap().into(),
+        )
+    }
+}
+
+impl CanEncoder {
+    pub fn new(orientation_id: Id, acceleration_id: Id) -> Self {
+        CanEncoder {
+            orientation_id,
+            acceleration_id,
+            counter: 0,
+        }
+    }
+
+    pub fn with_extended_ids(orientation_id: ExtendedId, acceleration_id: ExtendedId) -> Self {
+        CanEncoder::new(orientation_id.into(), acceleration_id.into())
+    }
+
+    /// Returns `[orientation, acceleration]`. Motion intent / request values
+    /// above 15 do not fit their 4-bit signals and are truncated. The
+    /// counter only advances when both frames were built.
+    pub fn encode<F: Frame>(&mut self, frame: &Bno08xRvcRawFrame) -> Result<[F; 2], CanError> {
+        let counter = self.counter;
+        let orientation = pack(
+            self.orientation_id,
+            [frame.yaw, frame.pitch, frame.roll],
+            counter,
+            frame.motion_intent & 0x0F,
+        );
+        let acceleration = pack(
+            self.acceleration_id,
+            [frame.x_acc, frame.y_acc, frame.z_acc],
+            counter,
+            frame.motion_request & 0x0F,
+        );
+        let frames = [
+            F::new(self.orientation_id, &orientation).ok_or(CanError::FrameRejected)?,
+            F::new(self.acceleration_id, &acceleration).ok_or(CanError::FrameRejected)?,
+        ];
+        self.counter = (self.counter + 1) & 0x0F;
+        Ok(frames)
+    }
+}
+
+/// Reassembles raw frames from orientation / acceleration pairs.
+pub struct CanDecoder {
+    orientation_id: Id,
+    acceleration_id: Id,
+    pending: Option<([i16; 3], u8, u8)>,
+    last_counter: Option<u8>,
+    lost_samples: u32,
+}
+
+impl Default for CanDecoder {
+    fn default() -> Self {
+        CanDecoder::new(
+            StandardId::new(CAN_ORIENTATION_ID).unwrap().into(),
+            StandardId::new(CAN_ACCELERATION_ID).unwrap().into(),
+        )
+    }
+}
+
+impl CanDecoder {
+    pub fn new(orientation_id: Id, acceleration_id: Id) -> Self {
+        CanDecoder {
+            orientation_id,
+            acceleration_id,
+            pending: None,
+            last_counter: None,
+            lost_samples: 0,
+        }
+    }
+
+    /// Feeds one received frame. Returns a raw frame once both halves of a
+    /// sample arrived; its index is the rolling counter, `rsvd` and `csum`
+    /// are 0.
+    pub fn push<F: Frame>(&mut self, frame: &F) -> Result<Option<Bno08xRvcRawFrame>, CanError> {
+        if frame.is_remote_frame() {
+            return Err(CanError::UnexpectedId);
+        }
+        let id = frame.id();
+        if id == self.orientation_id {
+            let unpacked = unpack(id, frame.data())?;
+            self.pending = Some(unpacked);
+            return Ok(None);
+        }
+        if id != self.acceleration_id {
+            return Err(CanError::UnexpectedId);
+        }
+        let (acc, counter, motion_request) = unpack(id, frame.data())?;
+        let (angles, _, motion_intent) = match self.pending.take() {
+            Some(pending) if pending.1 == counter => pending,
+            _ => return Err(CanError::CounterMismatch),
+        };
+        if let Some(last) = self.last_counter {
+            self.lost_samples += (counter.wrapping_sub(last).wrapping_sub(1) & 0x0F) as u32;
+        }
+        self.last_counter = Some(counter);
+        Ok(Some(Bno08xRvcRawFrame {
+            index: counter,
+            yaw: angles[0],
+            pitch: angles[1],
+            roll: angles[2],
+            x_acc: acc[0],
+            y_acc: acc[1],
+            z_acc: acc[2],
+            motion_intent,
+            motion_request,
+            rsvd: 0,
+            csum: 0,
+        }))
+    }
+
+    /// Samples skipped according to the rolling counter. Gaps of 16 or more
+    /// samples wrap and cannot be detected.
+    pub fn lost_samples(&self) -> u32 {
+        self.lost_samples
+    }
+
+    pub fn reset(&mut self) {
+        self.pending = None;
+        self.last_counter = None;
+        self.lost_samples = 0;
+    }
+}
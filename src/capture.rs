@@ -0,0 +1,204 @@
+//! Replays raw UART captures through `Processor` and `Parser`, so parser
+//! behaviour on field data (garbage, partial frames, bad checksums) can be
+//! reproduced byte for byte.
+//!
+//! A plain byte dump has no timing, so `run` can at most pace it at the
+//! UART line rate. `run_timed` replays timestamped chunks, such as the
+//! `Payload::Bytes` records of a `recording` log, at their original times,
+//! including the gaps between sensor frames.
+
+use crate::parser::{Bno08xRvcRawFrame, Parser};
+use crate::processor::Processor;
+#[cfg(feature = "recording")]
+use crate::recording::{Payload, ReplayError, Replayer};
+use crate::{Error, BNO08X_UART_RVC_BAUD_RATE};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Pacing {
+    /// Feed chunks back to back.
+    Unpaced,
+    /// Feed bytes back to back at the UART line rate (10 bits per byte at
+    /// `BNO08X_UART_RVC_BAUD_RATE`). The idle time between sensor frames is
+    /// not reproduced, use `run_timed` for that.
+    LineRate,
+}
+
+#[derive(Debug)]
+pub enum CaptureError {
+    /// `Processor` or `Parser` failed.
+    Rvc(Error),
+    /// The capture file could not be read.
+    Io(std::io::Error),
+    /// The `recording` log is not valid.
+    #[cfg(feature = "recording")]
+    Replay(ReplayError),
+}
+
+impl From<Error> for CaptureError {
+    fn from(e: Error) -> Self {
+        CaptureError::Rvc(e)
+    }
+}
+
+impl From<std::io::Error> for CaptureError {
+    fn from(e: std::io::Error) -> Self {
+        CaptureError::Io(e)
+    }
+}
+
+#[cfg(feature = "recording")]
+impl From<ReplayError> for CaptureError {
+    fn from(e: ReplayError) -> Self {
+        CaptureError::Replay(e)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct CaptureReplayStats {
+    pub bytes: usize,
+    pub chunks: usize,
+    /// Checksum-valid frames delivered by the parser.
+    pub frames: usize,
+    /// Chunks `Processor::process_slice` refused, as a firmware ignoring the
+    /// error would lose them.
+    pub dropped_chunks: usize,
+}
+
+pub struct CaptureReplay {
+    chunk_size: usize,
+    pacing: Pacing,
+}
+
+impl CaptureReplay {
+    /// `chunk_size` mimics how many bytes the UART ISR / DMA hands over at
+    /// once; it is clamped to at least 1.
+    pub fn new(chunk_size: usize, pacing: Pacing) -> Self {
+        CaptureReplay {
+            chunk_size: chunk_size.max(1),
+            pacing,
+        }
+    }
+
+    pub fn run<F: FnMut(&Bno08xRvcRawFrame)>(
+        &self,
+        capture: &[u8],
+        processor: &mut Processor,
+        parser: &mut Parser,
+        f: F,
+    ) -> Result<CaptureReplayStats, CaptureError> {
+        let mut bytes = 0;
+        let chunks = capture.chunks(self.chunk_size).map(|chunk| {
+            bytes += chunk.len();
+            let due = match self.pacing {
+                Pacing::Unpaced => Duration::ZERO,
+                Pacing::LineRate => byte_time(bytes),
+            };
+            (due, chunk)
+        });
+        self.feed(chunks, processor, parser, f)
+    }
+
+    /// Replays `(timestamp_us, bytes)` chunks, each fed no earlier than its
+    /// timestamp relative to the first one, whatever the `pacing`. Chunks
+    /// longer than `chunk_size` are split and the pieces fed together.
+    pub fn run_timed<'c, I, F>(
+        &self,
+        capture: I,
+        processor: &mut Processor,
+        parser: &mut Parser,
+        f: F,
+    ) -> Result<CaptureReplayStats, CaptureError>
+    where
+        I: IntoIterator<Item = (u64, &'c [u8])>,
+        F: FnMut(&Bno08xRvcRawFrame),
+    {
+        let mut first = None;
+        let chunk_size = self.chunk_size;
+        let chunks = capture.into_iter().flat_map(|(timestamp_us, bytes)| {
+            let start = *first.get_or_insert(timestamp_us);
+            let due = Duration::from_micros(timestamp_us.saturating_sub(start));
+            bytes.chunks(chunk_size).map(move |chunk| (due, chunk))
+        });
+        self.feed(chunks, processor, parser, f)
+    }
+
+    /// Replays the `Payload::Bytes` records of an in-memory `recording`
+    /// log at their recorded times.
+    #[cfg(feature = "recording")]
+    pub fn run_recording<F: FnMut(&Bno08xRvcRawFrame)>(
+        &self,
+        log: &mut [u8],
+        processor: &mut Processor,
+        parser: &mut Parser,
+        f: F,
+    ) -> Result<CaptureReplayStats, CaptureError> {
+        let mut error = None;
+        let chunks = Replayer::new(log)?.map_while(|record| match record {
+            Ok(replayed) => Some(replayed.record),
+            Err(e) => {
+                error = Some(e);
+                None
+            }
+        });
+        let chunks = chunks.filter_map(|record| match record.payload {
+            Payload::Bytes(bytes) => Some((record.timestamp_us, bytes)),
+            _ => None,
+        });
+        let stats = self.run_timed(chunks, processor, parser, f)?;
+        match error {
+            Some(e) => Err(e.into()),
+            None => Ok(stats),
+        }
+    }
+
+    fn feed<'c, I, F>(
+        &self,
+        chunks: I,
+        processor: &mut Processor,
+        parser: &mut Parser,
+        mut f: F,
+    ) -> Result<CaptureReplayStats, CaptureError>
+    where
+        I: Iterator<Item = (Duration, &'c [u8])>,
+        F: FnMut(&Bno08xRvcRawFrame),
+    {
+        let mut stats = CaptureReplayStats::default();
+        let start = Instant::now();
+        for (due, chunk) in chunks {
+            if let Some(wait) = due.checked_sub(start.elapsed()) {
+                std::thread::sleep(wait);
+            }
+            stats.bytes += chunk.len();
+            stats.chunks += 1;
+            match processor.process_slice(chunk) {
+                Ok(()) => {}
+                Err(Error::BbqError(bbqueue::Error::InsufficientSize)) => {
+                    stats.dropped_chunks += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+            parser.drain(|frame| {
+                stats.frames += 1;
+                f(frame)
+            })?;
+        }
+        Ok(stats)
+    }
+
+    pub fn run_file<P: AsRef<Path>, F: FnMut(&Bno08xRvcRawFrame)>(
+        &self,
+        path: P,
+        processor: &mut Processor,
+        parser: &mut Parser,
+        f: F,
+    ) -> Result<CaptureReplayStats, CaptureError> {
+        let capture = std::fs::read(path)?;
+        self.run(&capture, processor, parser, f)
+    }
+}
+
+fn byte_time(bytes: usize) -> Duration {
+    Duration::from_micros(bytes as u64 * 10 * 1_000_000 / BNO08X_UART_RVC_BAUD_RATE as u64)
+}
@@ -0,0 +1,148 @@
+//! Reference-frame conventions for pretty frames.
+//!
+//! The sensor convention assumed by this crate: a right-handed frame with
+//! Z up, body axes X forward / Y left / Z up, yaw counter-clockwise about Z
+//! seen from above, and zero yaw along the reference direction set at reset
+//! (treated as north below). Euler angles are applied yaw (Z), pitch (Y),
+//! roll (X), as in the datasheet.
+//!
+//! | convention | world axes          | body axes           | yaw            | pitch    | roll   | acc            |
+//! |------------|---------------------|---------------------|----------------|----------|--------|----------------|
+//! | `Sensor`   | north / west / up   | fwd / left / up     | `yaw`          | `pitch`  | `roll` | `( x,  y,  z)` |
+//! | `Enu`      | east / north / up   | fwd / left / up     | `yaw + 90°`    | `pitch`  | `roll` | `( x,  y,  z)` |
+//! | `Ned`      | north / east / down | fwd / right / down  | `-yaw`         | `-pitch` | `roll` | `( x, -y, -z)` |
+//!
+//! `Enu` matches ROS REP 103 (zero yaw facing east), `Ned` matches the
+//! aviation / PX4 convention (clockwise yaw, specific force of `-g` on Z
+//! at rest).
+
+use crate::parser::Bno08xRvcPrettyFrame;
+use crate::quaternion::Quaternion;
+use crate::units::wrap_degrees;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameConvention {
+    Sensor,
+    Enu,
+    Ned,
+}
+
+impl FrameConvention {
+    // (world rotation, body rotation) taking the sensor convention to `self`.
+    fn rotations(&self) -> (Quaternion, Quaternion) {
+        match self {
+            FrameConvention::Sensor => (Quaternion::IDENTITY, Quaternion::IDENTITY),
+            FrameConvention::Enu => (
+                Quaternion::from_axis_angle_degrees([0.0, 0.0, 1.0], 90.0),
+                Quaternion::IDENTITY,
+            ),
+            FrameConvention::Ned => {
+                let flip = Quaternion::from_axis_angle_degrees([1.0, 0.0, 0.0], 180.0);
+                (flip, flip)
+            }
+        }
+    }
+}
+
+/// Intrinsic Tait-Bryan rotation sequences. `Zyx` is the sensor's native
+/// yaw / pitch / roll order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EulerOrder {
+    Zyx,
+    Zxy,
+    Yxz,
+    Yzx,
+    Xyz,
+    Xzy,
+}
+
+/// Euler angles in degrees, `angles[0]` applied first.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EulerAngles {
+    pub order: EulerOrder,
+    pub angles: [f32; 3],
+}
+
+impl Quaternion {
+    /// Decomposes the rotation `R = R_a(angles[0]) * R_b(angles[1]) * R_c(angles[2])`
+    /// for the axes `a`, `b`, `c` of `order`. The middle angle is within ±90°.
+    pub fn to_euler(&self, order: EulerOrder) -> EulerAngles {
+        let r = self.to_rotation_matrix();
+        let asin = |v: f32| libm::asinf(v.clamp(-1.0, 1.0));
+        let (a, b, c) = match order {
+            EulerOrder::Zyx => (
+                libm::atan2f(r[1][0], r[0][0]),
+                asin(-r[2][0]),
+                libm::atan2f(r[2][1], r[2][2]),
+            ),
+            EulerOrder::Zxy => (
+                libm::atan2f(-r[0][1], r[1][1]),
+                asin(r[2][1]),
+                libm::atan2f(-r[2][0], r[2][2]),
+            ),
+            EulerOrder::Yxz => (
+                libm::atan2f(r[0][2], r[2][2]),
+                asin(-r[1][2]),
+                libm::atan2f(r[1][0], r[1][1]),
+            ),
+            EulerOrder::Yzx => (
+                libm::atan2f(-r[2][0], r[0][0]),
+                asin(r[1][0]),
+                libm::atan2f(-r[1][2], r[1][1]),
+            ),
+            EulerOrder::Xyz => (
+                libm::atan2f(-r[1][2], r[2][2]),
+                asin(r[0][2]),
+                libm::atan2f(-r[0][1], r[0][0]),
+            ),
+            EulerOrder::Xzy => (
+                libm::atan2f(r[2][1], r[1][1]),
+                asin(-r[0][1]),
+                libm::atan2f(r[0][2], r[0][0]),
+            ),
+        };
+        EulerAngles {
+            order,
+            angles: [a.to_degrees(), b.to_degrees(), c.to_degrees()],
+        }
+    }
+}
+
+/// Orientation and acceleration of a frame expressed in a given convention.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConventionalFrame {
+    pub convention: FrameConvention,
+    pub index: u8,
+    pub orientation: Quaternion, // body to world
+    pub yaw: f32,                // degrees, ZYX
+    pub pitch: f32,              // degrees, ZYX
+    pub roll: f32,               // degrees, ZYX
+    pub acc: [f32; 3],           // m/s2, body axes
+}
+
+impl ConventionalFrame {
+    pub fn euler(&self, order: EulerOrder) -> EulerAngles {
+        self.orientation.to_euler(order)
+    }
+}
+
+impl Bno08xRvcPrettyFrame {
+    pub fn to_convention(&self, convention: FrameConvention) -> ConventionalFrame {
+        let (world, body) = convention.rotations();
+        let orientation = (world * self.orientation() * body.conjugate()).normalized();
+        let [yaw, pitch, roll] = orientation.to_euler(EulerOrder::Zyx).angles;
+        ConventionalFrame {
+            convention,
+            index: self.index,
+            orientation,
+            yaw: wrap_degrees(yaw),
+            pitch,
+            roll: wrap_degrees(roll),
+            acc: body.rotate([self.x_acc, self.y_acc, self.z_acc]),
+        }
+    }
+}
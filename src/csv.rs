@@ -0,0 +1,244 @@
+//! CSV export and import of pretty frames.
+//!
+//! Columns always appear in this order, optional ones only when enabled:
+//! `timestamp_us,index,yaw,pitch,roll,x_acc,y_acc,z_acc,motion_intent,motion_request,rsvd`.
+//! Angles are in degrees, accelerations in m/s2. The reader matches columns
+//! by header name, so files written with any column selection, or
+//! reordered by a spreadsheet, read back.
+
+use crate::parser::Bno08xRvcPrettyFrame;
+use std::io::{BufRead, Write};
+use std::string::String;
+use std::vec::Vec;
+
+const TIMESTAMP: &str = "timestamp_us";
+const INDEX: &str = "index";
+const VALUES: [&str; 6] = ["yaw", "pitch", "roll", "x_acc", "y_acc", "z_acc"];
+const DIAGNOSTICS: [&str; 3] = ["motion_intent", "motion_request", "rsvd"];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CsvColumns {
+    pub timestamp: bool,
+    pub index: bool,
+    /// `motion_intent`, `motion_request` and `rsvd`.
+    pub diagnostics: bool,
+}
+
+impl Default for CsvColumns {
+    fn default() -> Self {
+        CsvColumns {
+            timestamp: true,
+            index: true,
+            diagnostics: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CsvError {
+    Io(std::io::Error),
+    MissingColumn(&'static str),
+    Parse { line: usize, column: &'static str },
+}
+
+impl From<std::io::Error> for CsvError {
+    fn from(e: std::io::Error) -> Self {
+        CsvError::Io(e)
+    }
+}
+
+pub struct CsvWriter<W: Write> {
+    writer: W,
+    columns: CsvColumns,
+    header_written: bool,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn new(writer: W, columns: CsvColumns) -> Self {
+        CsvWriter {
+            writer,
+            columns,
+            header_written: false,
+        }
+    }
+
+    /// Writes one row, preceded by the header on the first call.
+    /// `timestamp_us` is ignored unless the timestamp column is enabled.
+    pub fn write_frame(
+        &mut self,
+        timestamp_us: Option<u64>,
+        frame: &Bno08xRvcPrettyFrame,
+    ) -> std::io::Result<()> {
+        if !self.header_written {
+            self.write_header()?;
+            self.header_written = true;
+        }
+        let w = &mut self.writer;
+        if self.columns.timestamp {
+            match timestamp_us {
+                Some(ts) => write!(w, "{},", ts)?,
+                None => write!(w, ",")?,
+            }
+        }
+        if self.columns.index {
+            write!(w, "{},", frame.index)?;
+        }
+        write!(
+            w,
+            "{},{},{},{},{},{}",
+            frame.yaw, frame.pitch, frame.roll, frame.x_acc, frame.y_acc, frame.z_acc
+        )?;
+        if self.columns.diagnostics {
+            write!(
+                w,
+                ",{},{},{}",
+                frame.motion_intent, frame.motion_request, frame.rsvd
+            )?;
+        }
+        writeln!(w)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_header(&mut self) -> std::io::Result<()> {
+        let mut names = [""; 11];
+        let mut len = 0;
+        let mut push = |name| {
+            names[len] = name;
+            len += 1;
+        };
+        if self.columns.timestamp {
+            push(TIMESTAMP);
+        }
+        if self.columns.index {
+            push(INDEX);
+        }
+        VALUES.iter().for_each(|name| push(name));
+        if self.columns.diagnostics {
+            DIAGNOSTICS.iter().for_each(|name| push(name));
+        }
+        writeln!(self.writer, "{}", names[..len].join(","))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CsvRow {
+    pub timestamp_us: Option<u64>,
+    pub frame: Bno08xRvcPrettyFrame,
+}
+
+/// Reads rows written by `CsvWriter`. A missing `index` column is
+/// replaced by a wrapping row counter, missing diagnostics by zeros.
+pub struct CsvReader<R: BufRead> {
+    reader: R,
+    // Position of each known column in a row, `None` when absent.
+    timestamp: Option<usize>,
+    index: Option<usize>,
+    values: [usize; 6],
+    diagnostics: [Option<usize>; 3],
+    line: usize,
+    rows: u8,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, CsvError> {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let position = |name: &str| header.trim_end().split(',').position(|c| c.trim() == name);
+        let mut values = [0; 6];
+        for (slot, name) in values.iter_mut().zip(VALUES.iter()) {
+            *slot = position(name).ok_or(CsvError::MissingColumn(name))?;
+        }
+        Ok(CsvReader {
+            timestamp: position(TIMESTAMP),
+            index: position(INDEX),
+            values,
+            diagnostics: [
+                position(DIAGNOSTICS[0]),
+                position(DIAGNOSTICS[1]),
+                position(DIAGNOSTICS[2]),
+            ],
+            reader,
+            line: 1,
+            rows: 0,
+        })
+    }
+
+    fn parse_row(&mut self, text: &str) -> Result<CsvRow, CsvError> {
+        let cells: Vec<&str> = text.split(',').map(str::trim).collect();
+        let line = self.line;
+        let field = |pos: usize, column: &'static str| -> Result<&str, CsvError> {
+            cells
+                .get(pos)
+                .copied()
+                .ok_or(CsvError::Parse { line, column })
+        };
+        let number = |pos: usize, column: &'static str| -> Result<f32, CsvError> {
+            field(pos, column)?
+                .parse()
+                .map_err(|_| CsvError::Parse { line, column })
+        };
+        let byte = |pos: Option<usize>, column: &'static str| -> Result<u8, CsvError> {
+            match pos {
+                None => Ok(0),
+                Some(pos) => field(pos, column)?
+                    .parse()
+                    .map_err(|_| CsvError::Parse { line, column }),
+            }
+        };
+
+        let timestamp_us = match self.timestamp {
+            None => None,
+            Some(pos) => match field(pos, TIMESTAMP)? {
+                "" => None,
+                ts => Some(ts.parse().map_err(|_| CsvError::Parse {
+                    line,
+                    column: TIMESTAMP,
+                })?),
+            },
+        };
+        let index = match self.index {
+            None => self.rows,
+            Some(_) => byte(self.index, INDEX)?,
+        };
+        let frame = Bno08xRvcPrettyFrame {
+            index,
+            yaw: number(self.values[0], VALUES[0])?,
+            pitch: number(self.values[1], VALUES[1])?,
+            roll: number(self.values[2], VALUES[2])?,
+            x_acc: number(self.values[3], VALUES[3])?,
+            y_acc: number(self.values[4], VALUES[4])?,
+            z_acc: number(self.values[5], VALUES[5])?,
+            motion_intent: byte(self.diagnostics[0], DIAGNOSTICS[0])?,
+            motion_request: byte(self.diagnostics[1], DIAGNOSTICS[1])?,
+            rsvd: byte(self.diagnostics[2], DIAGNOSTICS[2])?,
+        };
+        self.rows = self.rows.wrapping_add(1);
+        Ok(CsvRow {
+            timestamp_us,
+            frame,
+        })
+    }
+}
+
+impl<R: BufRead> Iterator for CsvReader<R> {
+    type Item = Result<CsvRow, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut text = String::new();
+        loop {
+            text.clear();
+            match self.reader.read_line(&mut text) {
+                Err(e) => return Some(Err(CsvError::Io(e))),
+                Ok(0) => return None,
+                Ok(_) => {}
+            }
+            self.line += 1;
+            if !text.trim().is_empty() {
+                return Some(self.parse_row(text.trim_end()));
+            }
+        }
+    }
+}
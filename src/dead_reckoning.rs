@@ -0,0 +1,162 @@
+use crate::motion::SensorVariant;
+use crate::parser::Bno08xRvcPrettyFrame;
+use crate::units::G_ACCELERATION;
+use crate::BNO08X_UART_RVC_FRAME_RATE_HZ;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DeadReckoningConfig {
+    /// Part producing the frames. On a BNO086 the motion intent byte drives
+    /// zero-velocity updates; other parts fall back to the variance test.
+    pub variant: SensorVariant,
+    /// Total accel variance, summed over the three axes, in (m/s2)^2 below
+    /// which a full window counts as stationary.
+    pub stationary_variance: f32,
+    /// Worst-case residual accel bias (m/s2) after gravity compensation,
+    /// used to grow the reported drift bounds.
+    pub accel_bias: f32,
+}
+
+impl Default for DeadReckoningConfig {
+    fn default() -> Self {
+        DeadReckoningConfig {
+            variant: SensorVariant::Bno080,
+            stationary_variance: 0.002,
+            accel_bias: 0.05,
+        }
+    }
+}
+
+/// Upper bounds on the integration error accumulated so far.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriftBound {
+    pub velocity: f32, // m/s, since the last zero-velocity update
+    pub position: f32, // m, since `reset`
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeadReckoningEstimate {
+    pub velocity: [f32; 3], // world frame (Z up), m/s
+    pub position: [f32; 3], // world frame (Z up), m, relative to the first frame
+    pub stationary: bool,
+    pub drift: DriftBound,
+}
+
+/// Integrates gravity-compensated acceleration into world-frame velocity
+/// and position, with zero-velocity updates while stationary.
+///
+/// `N` is the window length of the variance test.
+pub struct DeadReckoning<const N: usize> {
+    config: DeadReckoningConfig,
+    samples: [[f32; 3]; N],
+    len: usize,
+    pos: usize,
+    last_index: Option<u8>,
+    last_acc: [f32; 3],
+    estimate: DeadReckoningEstimate,
+}
+
+impl<const N: usize> DeadReckoning<N> {
+    pub fn new(config: DeadReckoningConfig) -> Self {
+        DeadReckoning {
+            config,
+            samples: [[0.0; 3]; N],
+            len: 0,
+            pos: 0,
+            last_index: None,
+            last_acc: [0.0; 3],
+            estimate: DeadReckoningEstimate::default(),
+        }
+    }
+
+    /// Integrates one frame, deriving the time step from the frame index so
+    /// dropped frames are accounted for.
+    pub fn update(&mut self, frame: &Bno08xRvcPrettyFrame) -> DeadReckoningEstimate {
+        let dt = match self.last_index {
+            None => 0.0,
+            Some(last) => {
+                frame.index.wrapping_sub(last) as f32 / BNO08X_UART_RVC_FRAME_RATE_HZ as f32
+            }
+        };
+        self.update_with_dt(frame, dt)
+    }
+
+    /// Integrates one frame over an explicit time step in seconds.
+    pub fn update_with_dt(
+        &mut self,
+        frame: &Bno08xRvcPrettyFrame,
+        dt: f32,
+    ) -> DeadReckoningEstimate {
+        let acc = self.linear_acceleration(frame);
+        let first = self.last_index.is_none();
+        self.last_index = Some(frame.index);
+
+        let stationary = self.is_stationary(frame);
+        let est = &mut self.estimate;
+        est.stationary = stationary;
+        if stationary {
+            est.velocity = [0.0; 3];
+            est.drift.velocity = 0.0;
+        } else if !first {
+            for (axis, a1) in acc.iter().enumerate() {
+                let v0 = est.velocity[axis];
+                let v1 = v0 + 0.5 * (self.last_acc[axis] + a1) * dt;
+                est.velocity[axis] = v1;
+                est.position[axis] += 0.5 * (v0 + v1) * dt;
+            }
+            let v0 = est.drift.velocity;
+            est.drift.velocity += self.config.accel_bias * dt;
+            est.drift.position += 0.5 * (v0 + est.drift.velocity) * dt;
+        }
+        self.last_acc = acc;
+        *est
+    }
+
+    pub fn estimate(&self) -> DeadReckoningEstimate {
+        self.estimate
+    }
+
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.pos = 0;
+        self.last_index = None;
+        self.last_acc = [0.0; 3];
+        self.estimate = DeadReckoningEstimate::default();
+    }
+
+    fn linear_acceleration(&self, frame: &Bno08xRvcPrettyFrame) -> [f32; 3] {
+        let world = frame
+            .orientation()
+            .rotate([frame.x_acc, frame.y_acc, frame.z_acc]);
+        [world[0], world[1], world[2] - G_ACCELERATION]
+    }
+
+    fn is_stationary(&mut self, frame: &Bno08xRvcPrettyFrame) -> bool {
+        if N > 0 {
+            self.samples[self.pos] = [frame.x_acc, frame.y_acc, frame.z_acc];
+            self.pos = (self.pos + 1) % N;
+            if self.len < N {
+                self.len += 1;
+            }
+        }
+
+        if let Some(intent) = frame.decode_motion_intent(self.config.variant) {
+            return intent.is_stationary();
+        }
+        if N == 0 || self.len < N {
+            return false;
+        }
+        let mut variance = 0.0;
+        for axis in 0..3 {
+            let mean = self.samples.iter().map(|s| s[axis]).sum::<f32>() / N as f32;
+            variance += self
+                .samples
+                .iter()
+                .map(|s| (s[axis] - mean) * (s[axis] - mean))
+                .sum::<f32>()
+                / N as f32;
+        }
+        variance < self.config.stationary_variance
+    }
+}
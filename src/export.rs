@@ -0,0 +1,352 @@
+//! InfluxDB line protocol and JSON Lines export.
+//!
+//! Frames, stats and health events implement `LineRecord`, which names the
+//! measurement and its fields. Names are stable across releases:
+//!
+//! | type                   | measurement           | fields                                                         |
+//! |------------------------|-----------------------|----------------------------------------------------------------|
+//! | `Bno08xRvcPrettyFrame` | `rvc_frame`           | `index,yaw,pitch,roll,x_acc,y_acc,z_acc,motion_intent,motion_request` |
+//! | `Bno08xRvcRawFrame`    | `rvc_raw_frame`       | same names, in 0.01° and mg                                    |
+//! | `SpikeStats`           | `rvc_spike_stats`     | `frames,spiky_frames,x_acc,y_acc,z_acc`                        |
+//! | `VibrationStats`       | `rvc_vibration`       | `rms,peak,crest_factor`                                        |
+//! | `CaptureReplayStats`   | `rvc_capture_replay`  | `bytes,chunks,frames,dropped_chunks`                           |
+//! | `VibrationEvent`       | `rvc_vibration_event` | `kind,index,peak,rms`                                          |
+//! | `TiltAlarmEvent`       | `rvc_tilt_alarm`      | `event`                                                        |
+//! | `InvalidReason`        | `rvc_invalid_frame`   | `reason`                                                       |
+//!
+//! Timestamps are nanoseconds, as InfluxDB expects by default. A JSON line
+//! looks like
+//! `{"measurement":"rvc_tilt_alarm","timestamp_ns":1000,"tags":{"sensor":"imu0"},"fields":{"event":"raised"}}`.
+//! Non-finite floats are left out of line protocol and written as `null`
+//! in JSON.
+
+use std::io::Write;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::capture::CaptureReplayStats;
+use crate::parser::{Bno08xRvcPrettyFrame, Bno08xRvcRawFrame};
+use crate::spike::SpikeStats;
+use crate::tilt::TiltAlarmEvent;
+use crate::validation::InvalidReason;
+use crate::vibration::{VibrationEvent, VibrationEventKind, VibrationStats};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FieldValue {
+    Float(f32),
+    Int(i64),
+    Str(&'static str),
+}
+
+pub trait LineRecord {
+    fn measurement(&self) -> &'static str;
+    fn fields(&self) -> Vec<(&'static str, FieldValue)>;
+}
+
+impl LineRecord for Bno08xRvcPrettyFrame {
+    fn measurement(&self) -> &'static str {
+        "rvc_frame"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+        use FieldValue::*;
+        std::vec![
+            ("index", Int(self.index as i64)),
+            ("yaw", Float(self.yaw)),
+            ("pitch", Float(self.pitch)),
+            ("roll", Float(self.roll)),
+            ("x_acc", Float(self.x_acc)),
+            ("y_acc", Float(self.y_acc)),
+            ("z_acc", Float(self.z_acc)),
+            ("motion_intent", Int(self.motion_intent as i64)),
+            ("motion_request", Int(self.motion_request as i64)),
+        ]
+    }
+}
+
+impl LineRecord for Bno08xRvcRawFrame {
+    fn measurement(&self) -> &'static str {
+        "rvc_raw_frame"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+        use FieldValue::*;
+        std::vec![
+            ("index", Int(self.index as i64)),
+            ("yaw", Int(self.yaw as i64)),
+            ("pitch", Int(self.pitch as i64)),
+            ("roll", Int(self.roll as i64)),
+            ("x_acc", Int(self.x_acc as i64)),
+            ("y_acc", Int(self.y_acc as i64)),
+            ("z_acc", Int(self.z_acc as i64)),
+            ("motion_intent", Int(self.motion_intent as i64)),
+            ("motion_request", Int(self.motion_request as i64)),
+        ]
+    }
+}
+
+impl LineRecord for SpikeStats {
+    fn measurement(&self) -> &'static str {
+        "rvc_spike_stats"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+        use FieldValue::*;
+        std::vec![
+            ("frames", Int(self.frames as i64)),
+            ("spiky_frames", Int(self.spiky_frames as i64)),
+            ("x_acc", Int(self.x_acc as i64)),
+            ("y_acc", Int(self.y_acc as i64)),
+            ("z_acc", Int(self.z_acc as i64)),
+        ]
+    }
+}
+
+impl LineRecord for VibrationStats {
+    fn measurement(&self) -> &'static str {
+        "rvc_vibration"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+        use FieldValue::*;
+        std::vec![
+            ("rms", Float(self.rms)),
+            ("peak", Float(self.peak)),
+            ("crest_factor", Float(self.crest_factor)),
+        ]
+    }
+}
+
+impl LineRecord for CaptureReplayStats {
+    fn measurement(&self) -> &'static str {
+        "rvc_capture_replay"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+        use FieldValue::*;
+        std::vec![
+            ("bytes", Int(self.bytes as i64)),
+            ("chunks", Int(self.chunks as i64)),
+            ("frames", Int(self.frames as i64)),
+            ("dropped_chunks", Int(self.dropped_chunks as i64)),
+        ]
+    }
+}
+
+impl LineRecord for VibrationEvent {
+    fn measurement(&self) -> &'static str {
+        "rvc_vibration_event"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+        use FieldValue::*;
+        let kind = match self.kind {
+            VibrationEventKind::Shock => "shock",
+            VibrationEventKind::Vibration => "vibration",
+        };
+        std::vec![
+            ("kind", Str(kind)),
+            ("index", Int(self.index as i64)),
+            ("peak", Float(self.peak)),
+            ("rms", Float(self.rms)),
+        ]
+    }
+}
+
+impl LineRecord for TiltAlarmEvent {
+    fn measurement(&self) -> &'static str {
+        "rvc_tilt_alarm"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+        let event = match self {
+            TiltAlarmEvent::Raised => "raised",
+            TiltAlarmEvent::Cleared => "cleared",
+        };
+        std::vec![("event", FieldValue::Str(event))]
+    }
+}
+
+impl LineRecord for InvalidReason {
+    fn measurement(&self) -> &'static str {
+        "rvc_invalid_frame"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+        let reason = match self {
+            InvalidReason::YawOutOfRange => "yaw_out_of_range",
+            InvalidReason::PitchOutOfRange => "pitch_out_of_range",
+            InvalidReason::RollOutOfRange => "roll_out_of_range",
+            InvalidReason::AccelerationOutOfRange => "acceleration_out_of_range",
+            InvalidReason::OrientationJump => "orientation_jump",
+        };
+        std::vec![("reason", FieldValue::Str(reason))]
+    }
+}
+
+// Measurement names: backslash before `,` and space.
+fn escape_measurement(s: &str, out: &mut String) {
+    for c in s.chars() {
+        if matches!(c, ',' | ' ') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+// Tag keys and tag values: backslash before `,`, `=` and space.
+fn escape_tag(s: &str, out: &mut String) {
+    for c in s.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+fn escape_json(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&std::format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Writes records as InfluxDB line protocol, one line per record.
+pub struct InfluxWriter<W: Write> {
+    writer: W,
+    tags: Vec<(String, String)>,
+}
+
+impl<W: Write> InfluxWriter<W> {
+    pub fn new(writer: W) -> Self {
+        InfluxWriter {
+            writer,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Adds a tag written on every line, e.g. `("sensor", "imu0")`. Line
+    /// protocol has no empty tags, so a tag with an empty key or value is
+    /// ignored.
+    pub fn with_tag(mut self, key: &str, value: &str) -> Self {
+        if !key.is_empty() && !value.is_empty() {
+            self.tags.push((key.into(), value.into()));
+        }
+        self
+    }
+
+    /// Writes one line. Without `timestamp_ns` the server assigns the time.
+    /// Records whose fields are all non-finite are skipped, as a line needs
+    /// at least one field.
+    pub fn write<R: LineRecord>(
+        &mut self,
+        timestamp_ns: Option<u64>,
+        record: &R,
+    ) -> std::io::Result<()> {
+        let mut line = String::new();
+        escape_measurement(record.measurement(), &mut line);
+        for (key, value) in self.tags.iter() {
+            line.push(',');
+            escape_tag(key, &mut line);
+            line.push('=');
+            escape_tag(value, &mut line);
+        }
+        let mut separator = ' ';
+        for (key, value) in record.fields() {
+            let value = match value {
+                FieldValue::Float(v) if !v.is_finite() => continue,
+                FieldValue::Float(v) => std::format!("{}", v),
+                FieldValue::Int(v) => std::format!("{}i", v),
+                // Field names and strings are static and need no escaping.
+                FieldValue::Str(v) => std::format!("\"{}\"", v),
+            };
+            line.push(separator);
+            line.push_str(key);
+            line.push('=');
+            line.push_str(&value);
+            separator = ',';
+        }
+        if separator == ' ' {
+            return Ok(());
+        }
+        if let Some(ts) = timestamp_ns {
+            line.push_str(&std::format!(" {}", ts));
+        }
+        writeln!(self.writer, "{}", line)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Writes records as newline-delimited JSON objects with `measurement`,
+/// `timestamp_ns` (omitted when unknown), `tags` and `fields`.
+pub struct JsonLinesWriter<W: Write> {
+    writer: W,
+    tags: Vec<(String, String)>,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    pub fn new(writer: W) -> Self {
+        JsonLinesWriter {
+            writer,
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn with_tag(mut self, key: &str, value: &str) -> Self {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn write<R: LineRecord>(
+        &mut self,
+        timestamp_ns: Option<u64>,
+        record: &R,
+    ) -> std::io::Result<()> {
+        let mut line = String::from("{\"measurement\":");
+        escape_json(record.measurement(), &mut line);
+        if let Some(ts) = timestamp_ns {
+            line.push_str(&std::format!(",\"timestamp_ns\":{}", ts));
+        }
+        line.push_str(",\"tags\":{");
+        for (i, (key, value)) in self.tags.iter().enumerate() {
+            if i > 0 {
+                line.push(',');
+            }
+            escape_json(key, &mut line);
+            line.push(':');
+            escape_json(value, &mut line);
+        }
+        line.push_str("},\"fields\":{");
+        for (i, (key, value)) in record.fields().into_iter().enumerate() {
+            if i > 0 {
+                line.push(',');
+            }
+            escape_json(key, &mut line);
+            line.push(':');
+            match value {
+                FieldValue::Float(v) if !v.is_finite() => line.push_str("null"),
+                FieldValue::Float(v) => line.push_str(&std::format!("{}", v)),
+                FieldValue::Int(v) => line.push_str(&std::format!("{}", v)),
+                FieldValue::Str(v) => escape_json(v, &mut line),
+            }
+        }
+        line.push_str("}}");
+        writeln!(self.writer, "{}", line)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
@@ -0,0 +1,203 @@
+use crate::parser::Bno08xRvcPrettyFrame;
+use crate::units::wrap_degrees;
+use core::f32::consts::PI;
+
+const ANGLES: usize = 3;
+const CHANNELS: usize = 6;
+
+/// Smoothing stage over a stream of pretty frames.
+///
+/// Yaw, pitch and roll are treated as circular quantities, so a stream
+/// flipping between 179.9° and -179.9° settles around 180° instead of 0°.
+/// Index and motion bytes of the output are copied from the latest input.
+pub trait FrameFilter {
+    fn update(&mut self, frame: &Bno08xRvcPrettyFrame) -> Bno08xRvcPrettyFrame;
+    fn reset(&mut self);
+}
+
+fn channels(frame: &Bno08xRvcPrettyFrame) -> [f32; CHANNELS] {
+    [
+        frame.yaw,
+        frame.pitch,
+        frame.roll,
+        frame.x_acc,
+        frame.y_acc,
+        frame.z_acc,
+    ]
+}
+
+fn with_channels(frame: &Bno08xRvcPrettyFrame, ch: [f32; CHANNELS]) -> Bno08xRvcPrettyFrame {
+    Bno08xRvcPrettyFrame {
+        yaw: ch[0],
+        pitch: ch[1],
+        roll: ch[2],
+        x_acc: ch[3],
+        y_acc: ch[4],
+        z_acc: ch[5],
+        ..*frame
+    }
+}
+
+/// Boxcar average over the last `N` frames.
+pub struct MovingAverage<const N: usize> {
+    samples: [[f32; CHANNELS]; N],
+    len: usize,
+    pos: usize,
+}
+
+impl<const N: usize> MovingAverage<N> {
+    pub fn new() -> Self {
+        MovingAverage {
+            samples: [[0.0; CHANNELS]; N],
+            len: 0,
+            pos: 0,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+}
+
+impl<const N: usize> Default for MovingAverage<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> FrameFilter for MovingAverage<N> {
+    fn update(&mut self, frame: &Bno08xRvcPrettyFrame) -> Bno08xRvcPrettyFrame {
+        if N == 0 {
+            return *frame;
+        }
+        self.samples[self.pos] = channels(frame);
+        self.pos = (self.pos + 1) % N;
+        if self.len < N {
+            self.len += 1;
+        }
+
+        let mut sin_sum = [0.0f32; ANGLES];
+        let mut cos_sum = [0.0f32; ANGLES];
+        let mut lin_sum = [0.0f32; CHANNELS - ANGLES];
+        for sample in &self.samples[..self.len] {
+            for ch in 0..ANGLES {
+                let rad = sample[ch] * PI / 180.0;
+                sin_sum[ch] += libm::sinf(rad);
+                cos_sum[ch] += libm::cosf(rad);
+            }
+            for ch in ANGLES..CHANNELS {
+                lin_sum[ch - ANGLES] += sample[ch];
+            }
+        }
+
+        let mut out = [0.0f32; CHANNELS];
+        for ch in 0..ANGLES {
+            out[ch] = wrap_degrees(libm::atan2f(sin_sum[ch], cos_sum[ch]) * 180.0 / PI);
+        }
+        for ch in ANGLES..CHANNELS {
+            out[ch] = lin_sum[ch - ANGLES] / self.len as f32;
+        }
+        with_channels(frame, out)
+    }
+
+    fn reset(&mut self) {
+        self.len = 0;
+        self.pos = 0;
+    }
+}
+
+/// Exponential smoothing, `y += alpha * (x - y)`, with the angle error
+/// taken along the shortest arc.
+pub struct Exponential {
+    alpha: f32,
+    state: Option<[f32; CHANNELS]>,
+}
+
+impl Exponential {
+    /// `alpha` is clamped to `[0, 1]`; 1 passes input through unchanged.
+    pub fn new(alpha: f32) -> Self {
+        Exponential {
+            alpha: alpha.clamp(0.0, 1.0),
+            state: None,
+        }
+    }
+
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+}
+
+impl FrameFilter for Exponential {
+    fn update(&mut self, frame: &Bno08xRvcPrettyFrame) -> Bno08xRvcPrettyFrame {
+        let input = channels(frame);
+        let state = match self.state {
+            None => input,
+            Some(mut state) => {
+                for ch in 0..ANGLES {
+                    let err = wrap_degrees(input[ch] - state[ch]);
+                    state[ch] = wrap_degrees(state[ch] + self.alpha * err);
+                }
+                for ch in ANGLES..CHANNELS {
+                    state[ch] += self.alpha * (input[ch] - state[ch]);
+                }
+                state
+            }
+        };
+        self.state = Some(state);
+        with_channels(frame, state)
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// First-order (RC) low-pass filter defined by its cutoff frequency.
+pub struct LowPass {
+    inner: Exponential,
+}
+
+impl LowPass {
+    /// A sample rate that is not positive (or NaN) gives a pass-through
+    /// filter (alpha 1); a cutoff that is not positive (or NaN) holds the
+    /// first sample (alpha 0).
+    pub fn new(cutoff_hz: f32, sample_rate_hz: f32) -> Self {
+        let alpha = if sample_rate_hz.is_nan() || sample_rate_hz <= 0.0 {
+            1.0
+        } else if cutoff_hz.is_nan() || cutoff_hz <= 0.0 {
+            0.0
+        } else {
+            let dt = 1.0 / sample_rate_hz;
+            let rc = 1.0 / (2.0 * PI * cutoff_hz);
+            let alpha = dt / (rc + dt);
+            // Both infinite: 0 / 0.
+            if alpha.is_nan() {
+                1.0
+            } else {
+                alpha
+            }
+        };
+        LowPass {
+            inner: Exponential::new(alpha),
+        }
+    }
+
+    /// Low-pass at the sensor's native 100 Hz report rate.
+    pub fn with_cutoff(cutoff_hz: f32) -> Self {
+        Self::new(cutoff_hz, crate::BNO08X_UART_RVC_FRAME_RATE_HZ as f32)
+    }
+
+    pub fn alpha(&self) -> f32 {
+        self.inner.alpha()
+    }
+}
+
+impl FrameFilter for LowPass {
+    fn update(&mut self, frame: &Bno08xRvcPrettyFrame) -> Bno08xRvcPrettyFrame {
+        self.inner.update(frame)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+}
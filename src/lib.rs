@@ -1,16 +1,60 @@
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(warnings)]
 
 use bbqueue::BBBuffer;
 
+#[cfg(feature = "float")]
+pub mod angular_velocity;
+#[cfg(feature = "can")]
+pub mod can;
+#[cfg(feature = "std")]
+pub mod capture;
+#[cfg(feature = "float")]
+pub mod convention;
+#[cfg(all(feature = "std", feature = "float"))]
+pub mod csv;
+#[cfg(feature = "float")]
+pub mod dead_reckoning;
+#[cfg(all(feature = "std", feature = "float"))]
+pub mod export;
+#[cfg(feature = "float")]
+pub mod filter;
+#[cfg(feature = "mavlink")]
+pub mod mavlink;
+pub mod motion;
+#[cfg(feature = "nmea")]
+pub mod nmea;
 pub mod parser;
+#[cfg(feature = "float")]
+pub mod predict;
 pub mod processor;
+#[cfg(feature = "float")]
+pub mod quaternion;
+#[cfg(feature = "recording")]
+pub mod recording;
+#[cfg(feature = "float")]
+pub mod resample;
+#[cfg(feature = "ros")]
+pub mod ros;
+#[cfg(feature = "float")]
+pub mod spike;
+#[cfg(feature = "float")]
+pub mod stationary;
+pub mod telemetry;
+#[cfg(feature = "float")]
+pub mod tilt;
+#[cfg(feature = "float")]
+pub mod units;
+pub mod validation;
+#[cfg(feature = "float")]
+pub mod vibration;
 
 const BNO08X_UART_RVC_FRAME_SIZE: usize = 19;
 const BNO08X_UART_RVC_HEADER: u16 = 0xAAAA;
 const MAX_AMOUNT_OF_FRAMES: usize = 3;
 
 pub const BNO08X_UART_RVC_BAUD_RATE: u32 = 115_200;
+pub const BNO08X_UART_RVC_FRAME_RATE_HZ: u32 = 100;
 pub const BUFFER_SIZE: usize = BNO08X_UART_RVC_FRAME_SIZE * MAX_AMOUNT_OF_FRAMES;
 
 #[derive(Debug)]
@@ -30,7 +74,10 @@ pub fn create(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser::{Bno08xRvcPrettyFrame, Bno08xRvcRawFrame};
+    use crate::motion::{MotionIntent, MotionRequest, SensorVariant};
+    #[cfg(feature = "float")]
+    use crate::parser::Bno08xRvcPrettyFrame;
+    use crate::parser::Bno08xRvcRawFrame;
     use core::borrow::Borrow;
 
     const TEST_FRAME: Bno08xRvcRawFrame = Bno08xRvcRawFrame {
@@ -58,20 +105,20 @@ mod tests {
             Ok((proc, pars)) => Some((proc, pars)),
             Err(_) => None,
         };
-        assert_eq!(matches!(create_option, None), false);
+        assert!(create_option.is_some());
         let (mut processor, mut parser) = create_option.unwrap();
         let processor_result = processor.process_slice(&test_data);
-        assert_eq!(matches!(processor_result, Ok(())), true);
+        assert!(processor_result.is_ok());
         let worker_result = parser.worker(|frame| {
             assert_eq!(*frame, TEST_FRAME);
         });
-        assert_eq!(matches!(worker_result, Ok(())), true);
+        assert!(worker_result.is_ok());
         assert_eq!(parser.get_last_raw_frame(), Some(TEST_FRAME));
         let parser_result = parser.consumer.read();
-        assert_eq!(
-            matches!(parser_result, Err(bbqueue::Error::InsufficientSize)),
-            true
-        );
+        assert!(matches!(
+            parser_result,
+            Err(bbqueue::Error::InsufficientSize)
+        ));
     }
 
     #[test]
@@ -85,20 +132,20 @@ mod tests {
             Ok((proc, pars)) => Some((proc, pars)),
             Err(_) => None,
         };
-        assert_eq!(matches!(create_option, None), false);
+        assert!(create_option.is_some());
         let (mut processor, mut parser) = create_option.unwrap();
         let processor_result = processor.process_slice(&test_data);
-        assert_eq!(matches!(processor_result, Ok(())), true);
+        assert!(processor_result.is_ok());
         let worker_result = parser.worker(|_| {
             panic!();
         });
-        assert_eq!(matches!(worker_result, Ok(())), true);
+        assert!(worker_result.is_ok());
         assert_eq!(parser.get_last_raw_frame(), None);
         let parser_result = parser.consumer.read();
-        assert_eq!(
-            matches!(parser_result, Err(bbqueue::Error::InsufficientSize)),
-            true
-        );
+        assert!(matches!(
+            parser_result,
+            Err(bbqueue::Error::InsufficientSize)
+        ));
     }
 
     #[test]
@@ -112,20 +159,20 @@ mod tests {
             Ok((proc, pars)) => Some((proc, pars)),
             Err(_) => None,
         };
-        assert_eq!(matches!(create_option, None), false);
+        assert!(create_option.is_some());
         let (mut processor, mut parser) = create_option.unwrap();
         let processor_result = processor.process_slice(&test_data);
-        assert_eq!(matches!(processor_result, Ok(())), true);
+        assert!(processor_result.is_ok());
         let worker_result = parser.worker(|_| {
             panic!();
         });
-        assert_eq!(matches!(worker_result, Ok(())), true);
+        assert!(worker_result.is_ok());
         assert_eq!(parser.get_last_raw_frame(), None);
         let parser_result = parser.consumer.read();
-        assert_eq!(
-            matches!(parser_result, Err(bbqueue::Error::InsufficientSize)),
-            true
-        );
+        assert!(matches!(
+            parser_result,
+            Err(bbqueue::Error::InsufficientSize)
+        ));
     }
 
     #[test]
@@ -138,24 +185,24 @@ mod tests {
             Ok((proc, pars)) => Some((proc, pars)),
             Err(_) => None,
         };
-        assert_eq!(matches!(create_option, None), false);
+        assert!(create_option.is_some());
         let (mut processor, mut parser) = create_option.unwrap();
         let processor_result = processor.process_slice(&test_data);
-        assert_eq!(matches!(processor_result, Ok(())), true);
+        assert!(processor_result.is_ok());
         let worker_result = parser.worker(|_| {
             panic!();
         });
-        assert_eq!(matches!(worker_result, Ok(())), true);
+        assert!(worker_result.is_ok());
         assert_eq!(parser.get_last_raw_frame(), None);
         let parser_result = parser.consumer.read();
-        assert_eq!(
-            matches!(parser_result, Err(bbqueue::Error::InsufficientSize)),
-            false
-        );
-        assert_eq!(
-            matches!(parser_result, Err(bbqueue::Error::GrantInProgress)),
-            false
-        );
+        assert!(!matches!(
+            parser_result,
+            Err(bbqueue::Error::InsufficientSize)
+        ));
+        assert!(!matches!(
+            parser_result,
+            Err(bbqueue::Error::GrantInProgress)
+        ));
         assert_eq!(parser_result.unwrap().len(), BNO08X_UART_RVC_FRAME_SIZE - 5);
     }
 
@@ -170,24 +217,24 @@ mod tests {
             Ok((proc, pars)) => Some((proc, pars)),
             Err(_) => None,
         };
-        assert_eq!(matches!(create_option, None), false);
+        assert!(create_option.is_some());
         let (mut processor, mut parser) = create_option.unwrap();
         let processor_result = processor.process_slice(&test_data);
-        assert_eq!(matches!(processor_result, Ok(())), true);
+        assert!(processor_result.is_ok());
         let worker_result = parser.worker(|frame| {
             assert_eq!(*frame, TEST_FRAME);
         });
-        assert_eq!(matches!(worker_result, Ok(())), true);
+        assert!(worker_result.is_ok());
         assert_eq!(parser.get_last_raw_frame(), Some(TEST_FRAME));
         let parser_result = parser.consumer.read();
-        assert_eq!(
-            matches!(parser_result, Err(bbqueue::Error::InsufficientSize)),
-            false
-        );
-        assert_eq!(
-            matches!(parser_result, Err(bbqueue::Error::GrantInProgress)),
-            false
-        );
+        assert!(!matches!(
+            parser_result,
+            Err(bbqueue::Error::InsufficientSize)
+        ));
+        assert!(!matches!(
+            parser_result,
+            Err(bbqueue::Error::GrantInProgress)
+        ));
         assert_eq!(parser_result.unwrap().len(), 5);
     }
 
@@ -202,20 +249,20 @@ mod tests {
             Ok((proc, pars)) => Some((proc, pars)),
             Err(_) => None,
         };
-        assert_eq!(matches!(create_option, None), false);
+        assert!(create_option.is_some());
         let (mut processor, mut parser) = create_option.unwrap();
         let processor_result = processor.process_slice(&test_data);
-        assert_eq!(matches!(processor_result, Ok(())), true);
+        assert!(processor_result.is_ok());
         let worker_result = parser.worker(|_| {
             panic!();
         });
-        assert_eq!(matches!(worker_result, Ok(())), true);
+        assert!(worker_result.is_ok());
         assert_eq!(parser.get_last_raw_frame(), None);
         let parser_result = parser.consumer.read();
-        assert_eq!(
-            matches!(parser_result, Err(bbqueue::Error::InsufficientSize)),
-            true
-        );
+        assert!(matches!(
+            parser_result,
+            Err(bbqueue::Error::InsufficientSize)
+        ));
     }
 
     #[test]
@@ -230,24 +277,24 @@ mod tests {
             Ok((proc, pars)) => Some((proc, pars)),
             Err(_) => None,
         };
-        assert_eq!(matches!(create_option, None), false);
+        assert!(create_option.is_some());
         let (mut processor, mut parser) = create_option.unwrap();
         let processor_result = processor.process_slice(&test_data);
-        assert_eq!(matches!(processor_result, Ok(())), true);
+        assert!(processor_result.is_ok());
         let worker_result = parser.worker(|frame| {
             assert_eq!(*frame, TEST_FRAME);
         });
-        assert_eq!(matches!(worker_result, Ok(())), true);
+        assert!(worker_result.is_ok());
         assert_eq!(parser.get_last_raw_frame(), Some(TEST_FRAME));
         let parser_result = parser.consumer.read();
-        assert_eq!(
-            matches!(parser_result, Err(bbqueue::Error::InsufficientSize)),
-            false
-        );
-        assert_eq!(
-            matches!(parser_result, Err(bbqueue::Error::GrantInProgress)),
-            false
-        );
+        assert!(!matches!(
+            parser_result,
+            Err(bbqueue::Error::InsufficientSize)
+        ));
+        assert!(!matches!(
+            parser_result,
+            Err(bbqueue::Error::GrantInProgress)
+        ));
         assert_eq!(parser_result.unwrap().len(), 5);
     }
 
@@ -259,32 +306,26 @@ mod tests {
             Ok((proc, pars)) => Some((proc, pars)),
             Err(_) => None,
         };
-        assert_eq!(matches!(create_option, None), false);
+        assert!(create_option.is_some());
         let (mut processor, mut parser) = create_option.unwrap();
         let processor_result = processor.process_slice(&test_data);
-        assert_eq!(
-            matches!(
-                processor_result,
-                Err(Error::BbqError(bbqueue::Error::InsufficientSize))
-            ),
-            true
-        );
+        assert!(matches!(
+            processor_result,
+            Err(Error::BbqError(bbqueue::Error::InsufficientSize))
+        ));
         let worker_result = parser.worker(|_| {
             panic!();
         });
-        assert_eq!(
-            matches!(
-                worker_result,
-                Err(Error::BbqError(bbqueue::Error::InsufficientSize))
-            ),
-            true
-        );
+        assert!(matches!(
+            worker_result,
+            Err(Error::BbqError(bbqueue::Error::InsufficientSize))
+        ));
         assert_eq!(parser.get_last_raw_frame(), None);
         let parser_result = parser.consumer.read();
-        assert_eq!(
-            matches!(parser_result, Err(bbqueue::Error::InsufficientSize)),
-            true
-        );
+        assert!(matches!(
+            parser_result,
+            Err(bbqueue::Error::InsufficientSize)
+        ));
     }
 
     #[test]
@@ -295,35 +336,29 @@ mod tests {
             Ok((proc, pars)) => Some((proc, pars)),
             Err(_) => None,
         };
-        assert_eq!(matches!(create_option, None), false);
+        assert!(create_option.is_some());
         let (mut processor, mut parser) = create_option.unwrap();
         for (idx, iter) in test_data.iter().enumerate() {
             let byte = *iter;
             let processor_result = processor.process_slice(&[byte]);
-            assert_eq!(
-                matches!(
-                    processor_result,
-                    Err(Error::BbqError(bbqueue::Error::InsufficientSize))
-                ),
-                false
-            );
+            assert!(!matches!(
+                processor_result,
+                Err(Error::BbqError(bbqueue::Error::InsufficientSize))
+            ));
             let worker_result = parser.worker(|_| {
                 panic!();
             });
-            assert_eq!(
-                matches!(
-                    worker_result,
-                    Err(Error::BbqError(bbqueue::Error::InsufficientSize))
-                ),
-                false
-            );
+            assert!(!matches!(
+                worker_result,
+                Err(Error::BbqError(bbqueue::Error::InsufficientSize))
+            ));
             assert_eq!(parser.get_last_raw_frame(), None);
             match parser.consumer.read() {
                 Ok(_) => {
                     panic!("{:}", idx);
                 }
                 Err(e) => {
-                    assert_eq!(matches!(e, bbqueue::Error::InsufficientSize), true);
+                    assert!(matches!(e, bbqueue::Error::InsufficientSize));
                 }
             }
         }
@@ -343,19 +378,16 @@ mod tests {
             Ok((proc, pars)) => Some((proc, pars)),
             Err(_) => None,
         };
-        assert_eq!(matches!(create_option, None), false);
+        assert!(create_option.is_some());
         let (mut processor, mut parser) = create_option.unwrap();
         let mut flag_in_worker = 0;
         for iter in test_data.iter() {
             let byte = *iter;
             let processor_result = processor.process_slice(&[byte]);
-            assert_eq!(
-                matches!(
-                    processor_result,
-                    Err(Error::BbqError(bbqueue::Error::InsufficientSize))
-                ),
-                false
-            );
+            assert!(!matches!(
+                processor_result,
+                Err(Error::BbqError(bbqueue::Error::InsufficientSize))
+            ));
             parser
                 .worker(|frame| {
                     assert_eq!(*frame, TEST_FRAME);
@@ -369,7 +401,7 @@ mod tests {
                 panic!("Rgr is not empty!");
             }
             Err(e) => {
-                assert_eq!(matches!(e, bbqueue::Error::InsufficientSize), true);
+                assert!(matches!(e, bbqueue::Error::InsufficientSize));
             }
         }
     }
@@ -388,19 +420,16 @@ mod tests {
             Ok((proc, pars)) => Some((proc, pars)),
             Err(_) => None,
         };
-        assert_eq!(matches!(create_option, None), false);
+        assert!(create_option.is_some());
         let (mut processor, mut parser) = create_option.unwrap();
         let mut flag_in_worker = 0;
         for iter in test_data.iter() {
             let byte = *iter;
             let processor_result = processor.process_slice(&[byte]);
-            assert_eq!(
-                matches!(
-                    processor_result,
-                    Err(Error::BbqError(bbqueue::Error::InsufficientSize))
-                ),
-                false
-            );
+            assert!(!matches!(
+                processor_result,
+                Err(Error::BbqError(bbqueue::Error::InsufficientSize))
+            ));
             parser
                 .worker(|frame| {
                     assert_eq!(*frame, TEST_FRAME);
@@ -414,11 +443,1447 @@ mod tests {
                 panic!("Rgr is not empty!");
             }
             Err(e) => {
-                assert_eq!(matches!(e, bbqueue::Error::InsufficientSize), true);
+                assert!(matches!(e, bbqueue::Error::InsufficientSize));
+            }
+        }
+    }
+
+    #[test]
+    fn integer_output_test() {
+        assert_eq!(TEST_FRAME.yaw_centideg(), 1);
+        assert_eq!(TEST_FRAME.pitch_centideg(), -110);
+        assert_eq!(TEST_FRAME.roll_centideg(), 2085);
+        assert_eq!(TEST_FRAME.x_acc_milli_g(), -371);
+        assert_eq!(TEST_FRAME.y_acc_milli_g(), -20);
+        assert_eq!(TEST_FRAME.z_acc_milli_g(), 977);
+        assert_eq!(TEST_FRAME.x_acc_mm_s2(), -3638);
+        assert_eq!(TEST_FRAME.y_acc_mm_s2(), -196);
+        assert_eq!(TEST_FRAME.z_acc_mm_s2(), 9581);
+
+        let mut frame = TEST_FRAME;
+        frame.x_acc = i16::MAX;
+        frame.y_acc = i16::MIN;
+        assert_eq!(frame.x_acc_mm_s2(), 321_335);
+        assert_eq!(frame.y_acc_mm_s2(), -321_344);
+    }
+
+    #[test]
+    fn motion_decoding_depends_on_variant() {
+        let mut frame = TEST_FRAME;
+        frame.motion_intent = 2;
+        frame.motion_request = 9;
+        assert_eq!(frame.decode_motion_intent(SensorVariant::Bno085), None);
+        assert_eq!(frame.decode_motion_request(SensorVariant::Bno080), None);
+        assert_eq!(
+            frame.decode_motion_intent(SensorVariant::Bno086),
+            Some(MotionIntent::StationaryWithVibration)
+        );
+        assert_eq!(
+            frame.decode_motion_request(SensorVariant::Bno086),
+            Some(MotionRequest::Unknown(9))
+        );
+        assert_eq!(u8::from(MotionRequest::from(5)), 5);
+
+        static BB: BBBuffer<{ BUFFER_SIZE }> = BBBuffer::new();
+        let (mut processor, mut parser) = create(BB.borrow()).unwrap();
+        processor
+            .process_slice(&[
+                0xAA, 0xAA, 0xDE, 0x01, 0x00, 0x92, 0xFF, 0x25, 0x08, 0x8D, 0xFE, 0xEC, 0xFF, 0xD1,
+                0x03, 0x03, 0x01, 0x00, 0xEB,
+            ])
+            .unwrap();
+        parser.worker(|_| {}).unwrap();
+        assert_eq!(parser.get_last_motion_intent(), None);
+        parser.set_sensor_variant(SensorVariant::Bno086);
+        assert_eq!(
+            parser.get_last_motion_intent(),
+            Some(MotionIntent::InMotion)
+        );
+        assert_eq!(
+            parser.get_last_motion_request(),
+            Some(MotionRequest::StayStationary)
+        );
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn filters_handle_yaw_wrap() {
+        use crate::filter::{Exponential, FrameFilter, LowPass, MovingAverage};
+
+        let mut a = TEST_FRAME.as_pretty_frame();
+        a.yaw = 179.0;
+        let mut b = a;
+        b.yaw = -179.0;
+        b.z_acc = a.z_acc + 2.0;
+
+        let mut avg = MovingAverage::<4>::new();
+        avg.update(&a);
+        let out = avg.update(&b);
+        assert!((out.yaw.abs() - 180.0).abs() < 0.01, "yaw = {}", out.yaw);
+        assert!((out.z_acc - (a.z_acc + 1.0)).abs() < 0.001);
+        assert!(!avg.is_full());
+
+        let mut exp = Exponential::new(0.25);
+        exp.update(&a);
+        let out = exp.update(&b);
+        assert!((out.yaw - 179.5).abs() < 0.01, "yaw = {}", out.yaw);
+
+        let low_pass = LowPass::with_cutoff(5.0);
+        assert!((low_pass.alpha() - 0.2391).abs() < 0.001);
+        assert_eq!(LowPass::new(5.0, 0.0).alpha(), 1.0);
+        assert_eq!(LowPass::new(5.0, f32::NAN).alpha(), 1.0);
+        assert_eq!(LowPass::new(0.0, 100.0).alpha(), 0.0);
+        assert_eq!(LowPass::new(-1.0, 100.0).alpha(), 0.0);
+        assert_eq!(LowPass::new(f32::NAN, 100.0).alpha(), 0.0);
+        assert_eq!(LowPass::new(f32::INFINITY, 100.0).alpha(), 1.0);
+        assert_eq!(LowPass::new(f32::INFINITY, f32::INFINITY).alpha(), 1.0);
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn spike_filters_replace_single_outliers() {
+        use crate::spike::{HampelFilter, MedianFilter};
+
+        let base = TEST_FRAME.as_pretty_frame();
+        let mut spike = base;
+        spike.y_acc = 60.0;
+
+        let mut median = MedianFilter::<5>::new(2.0);
+        let mut hampel = HampelFilter::<5>::new(3.0, 0.5);
+        for i in 0..8 {
+            let mut frame = if i == 5 { spike } else { base };
+            frame.x_acc += 0.01 * i as f32;
+            let (m_out, m_replaced) = median.update(&frame);
+            let (h_out, h_replaced) = hampel.update(&frame);
+            assert_eq!(m_replaced.any(), i == 5);
+            assert_eq!(h_replaced.y_acc, i == 5);
+            assert_eq!(h_replaced.count(), (i == 5) as u32);
+            assert!((m_out.y_acc - base.y_acc).abs() < 0.001);
+            assert!((h_out.y_acc - base.y_acc).abs() < 0.001);
+        }
+        assert_eq!(median.stats().spiky_frames, 1);
+        assert_eq!(hampel.stats().y_acc, 1);
+        assert_eq!(hampel.stats().frames, 8);
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn quaternion_round_trips_euler_angles() {
+        use crate::quaternion::Quaternion;
+
+        let q = Quaternion::from_euler_degrees(120.0, -30.0, 45.0);
+        let (yaw, pitch, roll) = q.to_euler_degrees();
+        assert!((yaw - 120.0).abs() < 0.01);
+        assert!((pitch + 30.0).abs() < 0.01);
+        assert!((roll - 45.0).abs() < 0.01);
+        // 90° yaw turns the sensor X axis into world Y.
+        let v = Quaternion::from_euler_degrees(90.0, 0.0, 0.0).rotate([1.0, 0.0, 0.0]);
+        assert!(v[0].abs() < 1e-6 && (v[1] - 1.0).abs() < 1e-6 && v[2].abs() < 1e-6);
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn dead_reckoning_integrates_and_zero_velocity_resets() {
+        use crate::dead_reckoning::{DeadReckoning, DeadReckoningConfig};
+        use crate::units::G_ACCELERATION;
+
+        let mut rest = TEST_FRAME.as_pretty_frame();
+        rest.yaw = 0.0;
+        rest.pitch = 0.0;
+        rest.roll = 0.0;
+        rest.x_acc = 0.0;
+        rest.y_acc = 0.0;
+        rest.z_acc = G_ACCELERATION;
+        rest.motion_intent = 3;
+
+        let mut dr = DeadReckoning::<10>::new(DeadReckoningConfig::default());
+        // 1 m/s2 along X for 1 s, with one dropped frame in the middle.
+        for i in 0..=101u32 {
+            if i == 50 {
+                continue;
+            }
+            let mut frame = rest;
+            frame.index = (i % 256) as u8;
+            frame.x_acc = 1.0 + if i % 2 == 0 { 0.3 } else { -0.3 };
+            dr.update(&frame);
+        }
+        let est = dr.estimate();
+        assert!(!est.stationary);
+        assert!(
+            (est.velocity[0] - 1.01).abs() < 0.02,
+            "v = {:?}",
+            est.velocity
+        );
+        assert!(
+            (est.position[0] - 0.51).abs() < 0.02,
+            "p = {:?}",
+            est.position
+        );
+        assert!((est.drift.velocity - 0.0505).abs() < 0.001);
+
+        for i in 0..10u8 {
+            let mut frame = rest;
+            frame.index = 102 + i;
+            dr.update(&frame);
+        }
+        let est = dr.estimate();
+        assert!(est.stationary);
+        assert_eq!(est.velocity, [0.0; 3]);
+        assert_eq!(est.drift.velocity, 0.0);
+
+        let mut bno086 = DeadReckoning::<10>::new(DeadReckoningConfig {
+            variant: SensorVariant::Bno086,
+            ..DeadReckoningConfig::default()
+        });
+        let mut frame = rest;
+        frame.motion_intent = 1;
+        assert!(bno086.update(&frame).stationary);
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn tilt_and_tilt_alarm() {
+        use crate::tilt::{TiltAlarm, TiltAlarmEvent};
+        use crate::units::{Angle, G_ACCELERATION};
+
+        let mut frame = TEST_FRAME.as_pretty_frame();
+        frame.pitch = 0.0;
+        frame.roll = 20.0;
+        let tilt = frame.tilt();
+        assert!((tilt.inclination.degrees() - 20.0).abs() < 0.01);
+        assert!((tilt.azimuth.degrees() + 90.0).abs() < 0.01);
+
+        let (s, c) = (20.0f32.to_radians().sin(), 20.0f32.to_radians().cos());
+        frame.x_acc = 0.0;
+        frame.y_acc = s * G_ACCELERATION;
+        frame.z_acc = c * G_ACCELERATION;
+        let from_acc = frame.tilt_from_acc().unwrap();
+        assert!((from_acc.inclination.degrees() - 20.0).abs() < 0.01);
+        assert!((from_acc.azimuth.degrees() + 90.0).abs() < 0.01);
+
+        // Combined pitch and roll: the accelerometer agrees with the angles.
+        frame.pitch = 30.0;
+        frame.roll = 40.0;
+        let (sp, cp) = libm::sincosf(30.0f32.to_radians());
+        let (sr, cr) = libm::sincosf(40.0f32.to_radians());
+        frame.x_acc = -sp * G_ACCELERATION;
+        frame.y_acc = cp * sr * G_ACCELERATION;
+        frame.z_acc = cp * cr * G_ACCELERATION;
+        let from_angles = frame.tilt();
+        let from_acc = frame.tilt_from_acc().unwrap();
+        assert!((from_angles.inclination.degrees() - from_acc.inclination.degrees()).abs() < 0.01);
+        assert!((from_angles.azimuth.degrees() - from_acc.azimuth.degrees()).abs() < 0.01);
+        frame.pitch = 0.0;
+
+        let mut alarm = TiltAlarm::new(Angle::from_degrees(15.0), Angle::from_degrees(2.0), 200);
+        // Above 15° from sample 10, below 13° from sample 50: both events
+        // come 200 ms (20 samples) after the first sample past the limit.
+        let mut events = [None; 80];
+        for (i, event) in events.iter_mut().enumerate() {
+            frame.index = i as u8;
+            frame.roll = match i {
+                0..=9 => 10.0,
+                10..=39 => 16.0,
+                40..=49 => 14.0,
+                _ => 12.0,
+            };
+            *event = alarm.update(&frame);
+            assert_eq!(alarm.is_active(), (30..70).contains(&i), "sample {}", i);
+        }
+        assert_eq!(events[30], Some(TiltAlarmEvent::Raised));
+        assert_eq!(events[70], Some(TiltAlarmEvent::Cleared));
+        assert_eq!(events.iter().filter(|e| e.is_some()).count(), 2);
+        assert!(!alarm.is_active());
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn vibration_monitor_reports_shock_and_vibration() {
+        use crate::units::G_ACCELERATION;
+        use crate::vibration::{VibrationConfig, VibrationEventKind, VibrationMonitor};
+
+        let mut frame = TEST_FRAME.as_pretty_frame();
+        frame.x_acc = 0.0;
+        frame.y_acc = 0.0;
+        let mut monitor = VibrationMonitor::<4>::new(VibrationConfig {
+            shock_threshold: 20.0,
+            vibration_threshold: 1.0,
+        });
+
+        frame.z_acc = G_ACCELERATION;
+        assert!(monitor.update(&frame).is_empty());
+        assert_eq!(monitor.stats().crest_factor, 0.0);
+
+        // The shock also lifts the window RMS: both events carry index 1.
+        frame.index = 1;
+        frame.z_acc = G_ACCELERATION + 30.0;
+        let events = monitor.update(&frame);
+        let shock = events.shock.unwrap();
+        assert_eq!(shock.kind, VibrationEventKind::Shock);
+        assert_eq!(shock.index, 1);
+        assert!((shock.peak - 30.0).abs() < 0.001);
+        let vibration = events.vibration.unwrap();
+        assert_eq!(vibration.kind, VibrationEventKind::Vibration);
+        assert_eq!(vibration.index, 1);
+        assert!((vibration.rms - 21.2132).abs() < 0.001);
+        assert_eq!(events.iter().count(), 2);
+
+        frame.index = 2;
+        frame.z_acc = G_ACCELERATION;
+        assert!(monitor.update(&frame).is_empty());
+        let stats = monitor.stats();
+        assert!((stats.rms - 17.3205).abs() < 0.001);
+        assert!((stats.crest_factor - 1.7320).abs() < 0.001);
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn stationary_detector_classifies_like_bno086() {
+        use crate::stationary::{StationaryConfig, StationaryDetector};
+        use crate::units::G_ACCELERATION;
+
+        let mut frame = TEST_FRAME.as_pretty_frame();
+        frame.x_acc = 0.0;
+        frame.y_acc = 0.0;
+        frame.z_acc = G_ACCELERATION;
+        frame.yaw = 179.9;
+        let mut detector = StationaryDetector::<5>::new(StationaryConfig::default());
+
+        let mut intent = MotionIntent::Indeterminate;
+        for i in 0..5u8 {
+            frame.index = 254u8.wrapping_add(i);
+            frame.yaw = if i % 2 == 0 { 179.99 } else { -179.99 };
+            intent = detector.update(&frame);
+            assert_eq!(intent == MotionIntent::Indeterminate, i < 4);
+        }
+        assert_eq!(intent, MotionIntent::StationaryWithoutVibration);
+
+        for i in 0..5u8 {
+            frame.index = frame.index.wrapping_add(1);
+            frame.x_acc = if i % 2 == 0 { 0.15 } else { -0.15 };
+            intent = detector.update(&frame);
+        }
+        assert_eq!(intent, MotionIntent::StationaryWithVibration);
+
+        for i in 0..5u8 {
+            frame.index = frame.index.wrapping_add(1);
+            frame.yaw = 10.0 * i as f32;
+            frame.x_acc = 4.0;
+            intent = detector.update(&frame);
+        }
+        assert_eq!(intent, MotionIntent::InMotionAccelerating);
+
+        frame.motion_intent = 3;
+        assert_eq!(
+            detector.update_for(SensorVariant::Bno086, &frame),
+            MotionIntent::InMotion
+        );
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn resampler_slerps_across_wrap_and_limits_extrapolation() {
+        use crate::resample::{FixedRateClock, ResampleError, Resampler, TimestampedFrame};
+
+        let mut a = TEST_FRAME.as_pretty_frame();
+        a.yaw = 170.0;
+        a.pitch = 0.0;
+        a.roll = 0.0;
+        a.x_acc = 0.0;
+        let mut b = a;
+        b.index = a.index.wrapping_add(1);
+        b.yaw = -170.0;
+        b.x_acc = 2.0;
+
+        let mut resampler = Resampler::<4>::new(5_000);
+        resampler.push(TimestampedFrame {
+            timestamp_us: 0,
+            frame: a,
+        });
+        assert_eq!(resampler.sample(0), Err(ResampleError::NotEnoughFrames));
+        resampler.push(TimestampedFrame {
+            timestamp_us: 10_000,
+            frame: b,
+        });
+
+        let mid = resampler.sample(5_000).unwrap().frame;
+        assert!((mid.yaw.abs() - 180.0).abs() < 0.01, "yaw = {}", mid.yaw);
+        assert!((mid.x_acc - 1.0).abs() < 0.001);
+        assert_eq!(mid.index, a.index);
+
+        let ahead = resampler.sample(12_500).unwrap().frame;
+        assert!((ahead.yaw + 165.0).abs() < 0.01, "yaw = {}", ahead.yaw);
+        assert_eq!(ahead.index, b.index);
+        assert_eq!(resampler.sample(15_001), Err(ResampleError::BeyondHorizon));
+
+        let mut clock = FixedRateClock::from_rate_hz(200);
+        assert_eq!(
+            clock.poll(&resampler).unwrap().unwrap().timestamp_us,
+            10_000
+        );
+        assert_eq!(clock.poll(&resampler), None);
+
+        // An unlimited horizon and a zero rate do not overflow or divide by 0.
+        let mut unlimited = Resampler::<4>::new(u64::MAX);
+        for (timestamp_us, frame) in [(0, a), (10_000, b)] {
+            unlimited.push(TimestampedFrame {
+                timestamp_us,
+                frame,
+            });
+        }
+        assert!(unlimited.sample(1_000_000).is_ok());
+        let mut clock = FixedRateClock::from_rate_hz(0);
+        assert!(clock.poll(&unlimited).is_some());
+        assert_eq!(clock.poll(&unlimited), None);
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn frame_conventions_and_euler_orders() {
+        use crate::convention::{EulerOrder, FrameConvention};
+        use crate::quaternion::Quaternion;
+
+        let mut frame = TEST_FRAME.as_pretty_frame();
+        frame.yaw = 30.0;
+        frame.pitch = 10.0;
+        frame.roll = -20.0;
+        frame.x_acc = 1.0;
+        frame.y_acc = 2.0;
+        frame.z_acc = 9.0;
+
+        let close = |a: f32, b: f32| (a - b).abs() < 0.01;
+        let sensor = frame.to_convention(FrameConvention::Sensor);
+        assert!(close(sensor.yaw, 30.0) && close(sensor.pitch, 10.0) && close(sensor.roll, -20.0));
+        let enu = frame.to_convention(FrameConvention::Enu);
+        assert!(close(enu.yaw, 120.0) && close(enu.pitch, 10.0) && close(enu.roll, -20.0));
+        assert!(close(enu.acc[1], 2.0));
+        let ned = frame.to_convention(FrameConvention::Ned);
+        assert!(close(ned.yaw, -30.0), "yaw = {}", ned.yaw);
+        assert!(close(ned.pitch, -10.0) && close(ned.roll, -20.0));
+        assert!(close(ned.acc[0], 1.0) && close(ned.acc[1], -2.0) && close(ned.acc[2], -9.0));
+
+        let axes = |order| match order {
+            EulerOrder::Zyx => [2, 1, 0],
+            EulerOrder::Zxy => [2, 0, 1],
+            EulerOrder::Yxz => [1, 0, 2],
+            EulerOrder::Yzx => [1, 2, 0],
+            EulerOrder::Xyz => [0, 1, 2],
+            EulerOrder::Xzy => [0, 2, 1],
+        };
+        let angles = [25.0, -40.0, 70.0];
+        for order in [
+            EulerOrder::Zyx,
+            EulerOrder::Zxy,
+            EulerOrder::Yxz,
+            EulerOrder::Yzx,
+            EulerOrder::Xyz,
+            EulerOrder::Xzy,
+        ] {
+            let mut q = Quaternion::IDENTITY;
+            for (axis, angle) in axes(order).iter().zip(angles.iter()) {
+                let mut v = [0.0; 3];
+                v[*axis] = 1.0;
+                q = q * Quaternion::from_axis_angle_degrees(v, *angle);
+            }
+            let euler = q.to_euler(order);
+            for (got, want) in euler.angles.iter().zip(angles.iter()) {
+                assert!(close(*got, *want), "{:?}: {:?}", order, euler.angles);
+            }
+        }
+        let zyx = sensor.euler(EulerOrder::Zyx).angles;
+        assert!(close(zyx[0], 30.0) && close(zyx[1], 10.0) && close(zyx[2], -20.0));
+    }
+
+    #[test]
+    fn validator_rejects_implausible_frames() {
+        use crate::validation::{InvalidReason, ValidationLimits, ValidationMode, Validator};
+
+        let mut validator = Validator::new(ValidationLimits::default(), ValidationMode::Flag);
+        assert_eq!(validator.check(&TEST_FRAME), Ok(()));
+
+        let mut frame = TEST_FRAME;
+        frame.index = TEST_FRAME.index.wrapping_add(1);
+        frame.pitch = 9_100;
+        assert_eq!(validator.check(&frame), Err(InvalidReason::PitchOutOfRange));
+        frame.pitch = TEST_FRAME.pitch;
+        frame.z_acc = 20_000;
+        assert_eq!(
+            validator.check(&frame),
+            Err(InvalidReason::AccelerationOutOfRange)
+        );
+
+        // Yaw going 179.9° -> -179.9° is a 0.2° step, not a jump.
+        let mut wrap = Validator::new(ValidationLimits::default(), ValidationMode::Flag);
+        frame = TEST_FRAME;
+        frame.yaw = 17_990;
+        assert_eq!(wrap.check(&frame), Ok(()));
+        frame.index = frame.index.wrapping_add(1);
+        frame.yaw = -17_990;
+        assert_eq!(wrap.check(&frame), Ok(()));
+        frame.index = frame.index.wrapping_add(1);
+        frame.yaw = 0;
+        assert_eq!(wrap.check(&frame), Err(InvalidReason::OrientationJump));
+
+        // In reject mode the parser drops the frame and reports why.
+        let test_data: [u8; BNO08X_UART_RVC_FRAME_SIZE] = [
+            0xAA, 0xAA, 0xDE, 0x01, 0x00, 0x92, 0xFF, 0x25, 0x08, 0x8D, 0xFE, 0xEC, 0xFF, 0xD1,
+            0x03, 0x00, 0x00, 0x00, 0xE7,
+        ];
+        static BB: BBBuffer<{ BUFFER_SIZE }> = BBBuffer::new();
+        let (mut processor, mut parser) = create(BB.borrow()).unwrap();
+        parser.set_validator(Some(Validator::new(
+            ValidationLimits {
+                max_abs_acc_milli_g: 500,
+                ..ValidationLimits::default()
+            },
+            ValidationMode::Reject,
+        )));
+        processor.process_slice(&test_data).unwrap();
+        parser.worker(|_| panic!()).unwrap();
+        assert_eq!(parser.get_last_raw_frame(), None);
+        assert_eq!(
+            parser.get_last_invalid_reason(),
+            Some(InvalidReason::AccelerationOutOfRange)
+        );
+        assert!(matches!(
+            parser.consumer.read(),
+            Err(bbqueue::Error::InsufficientSize)
+        ));
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn angular_velocity_across_wrap_and_dropped_frames() {
+        use crate::angular_velocity::AngularVelocityEstimator;
+
+        let mut frame = TEST_FRAME.as_pretty_frame();
+        frame.pitch = 0.0;
+        frame.roll = 0.0;
+        frame.index = 250;
+        frame.yaw = 178.0;
+        let mut estimator = AngularVelocityEstimator::default();
+        assert_eq!(estimator.update(&frame), None);
+
+        // +1° per 10 ms across the wrap, with two frames dropped.
+        frame.index = frame.index.wrapping_add(3);
+        frame.yaw = -179.0;
+        let rate = estimator.update(&frame).unwrap().degrees_per_second();
+        assert!((rate[2] - 100.0).abs() < 0.1, "rate = {:?}", rate);
+        assert!(rate[0].abs() < 0.01 && rate[1].abs() < 0.01);
+
+        // Roll in a yawed body shows up on the body X axis.
+        frame.index = frame.index.wrapping_add(1);
+        frame.roll = 0.5;
+        let rate = estimator.update(&frame).unwrap().degrees_per_second();
+        assert!((rate[0] - 50.0).abs() < 0.1 && rate[2].abs() < 0.1);
+
+        frame.index = frame.index.wrapping_add(100);
+        assert_eq!(estimator.update(&frame), None);
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn rotation_between_frames() {
+        let mut a = TEST_FRAME.as_pretty_frame();
+        a.yaw = 170.0;
+        a.pitch = 0.0;
+        a.roll = 0.0;
+        let mut b = a;
+        b.yaw = -160.0;
+
+        let rotation = a.rotation_to(&b);
+        assert!((rotation.angle.degrees() - 30.0).abs() < 0.01);
+        assert!((rotation.yaw_delta.degrees() - 30.0).abs() < 0.01);
+        assert!((rotation.axis[2] - 1.0).abs() < 1e-4);
+
+        let back = b.rotation_to(&a);
+        assert!((back.yaw_delta.degrees() + 30.0).abs() < 0.01);
+        assert!((back.axis[2] + 1.0).abs() < 1e-4);
+
+        b.yaw = a.yaw;
+        b.pitch = 90.0;
+        let rotation = a.rotation_to(&b);
+        assert!((rotation.angle.degrees() - 90.0).abs() < 0.01);
+        assert!((rotation.axis[1] - 1.0).abs() < 1e-4);
+        assert!(rotation.yaw_delta.degrees().abs() < 0.01);
+        assert_eq!(a.rotation_to(&a).angle.degrees(), 0.0);
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn predictor_extrapolates_by_latency() {
+        use crate::angular_velocity::AngularVelocityEstimator;
+        use crate::predict::{Predictor, UART_FRAME_LATENCY_US};
+
+        assert_eq!(UART_FRAME_LATENCY_US, 1649);
+        let mut predictor = Predictor::new(20_000, AngularVelocityEstimator::default());
+        let mut frame = TEST_FRAME.as_pretty_frame();
+        frame.pitch = 0.0;
+        frame.roll = 0.0;
+        frame.yaw = 178.0;
+        assert_eq!(predictor.update(&frame), frame);
+
+        // 100 °/s yaw, predicted 20 ms ahead across the wrap.
+        frame.index = frame.index.wrapping_add(1);
+        frame.yaw = 179.0;
+        let predicted = predictor.update(&frame);
+        assert!(
+            (predicted.yaw + 179.0).abs() < 0.05,
+            "yaw = {}",
+            predicted.yaw
+        );
+        assert_eq!(predicted.z_acc, frame.z_acc);
+        assert_eq!(predicted.index, frame.index);
+    }
+
+    #[cfg(all(feature = "serde", feature = "float"))]
+    #[test]
+    fn frames_and_events_round_trip_through_serde() {
+        use crate::parser::Bno08xRvcPrettyFrame;
+        use crate::vibration::{VibrationEvent, VibrationEventKind};
+
+        let mut buf = [0u8; 64];
+        let bytes = postcard::to_slice(&TEST_FRAME, &mut buf).unwrap();
+        assert_eq!(bytes.len(), BNO08X_UART_RVC_FRAME_SIZE - 2);
+        assert_eq!(
+            postcard::from_bytes::<Bno08xRvcRawFrame>(bytes),
+            Ok(TEST_FRAME)
+        );
+
+        let pretty = TEST_FRAME.as_pretty_frame();
+        let bytes = postcard::to_slice(&pretty, &mut buf).unwrap();
+        assert_eq!(
+            postcard::from_bytes::<Bno08xRvcPrettyFrame>(bytes),
+            Ok(pretty)
+        );
+
+        let event = VibrationEvent {
+            kind: VibrationEventKind::Shock,
+            index: 7,
+            peak: 31.5,
+            rms: 2.0,
+        };
+        let bytes = postcard::to_slice(&event, &mut buf).unwrap();
+        assert_eq!(postcard::from_bytes::<VibrationEvent>(bytes), Ok(event));
+
+        let intent = MotionIntent::Unknown(42);
+        let bytes = postcard::to_slice(&intent, &mut buf).unwrap();
+        assert_eq!(postcard::from_bytes::<MotionIntent>(bytes), Ok(intent));
+    }
+
+    #[cfg(feature = "recording")]
+    #[test]
+    fn recording_round_trip() {
+        use crate::recording::{Payload, Recorder, ReplayError, Replayer};
+
+        let mut log = [0u8; 512];
+        let free = {
+            let mut recorder = Recorder::new(&mut log[..]).unwrap();
+            recorder.record_frame(1_000, &TEST_FRAME).unwrap();
+            recorder.record_bytes(11_000, &[0xAA, 0x00, 0xAA]).unwrap();
+            recorder.record_frame(21_000, &TEST_FRAME).unwrap();
+            recorder.into_inner().len()
+        };
+        let used = log.len() - free;
+
+        let mut replayer = Replayer::new(&mut log[..used]).unwrap();
+        let first = replayer.next().unwrap().unwrap();
+        assert_eq!(first.record.payload, Payload::Frame(TEST_FRAME));
+        let second = replayer.next().unwrap().unwrap();
+        assert_eq!(second.record.payload, Payload::Bytes(&[0xAA, 0x00, 0xAA]));
+        assert_eq!(second.delay_us, 10_000);
+        let third = replayer.next().unwrap().unwrap();
+        assert_eq!(third.record.timestamp_us, 21_000);
+        assert!(replayer.next().is_none());
+
+        #[cfg(feature = "std")]
+        {
+            use crate::recording::StdSink;
+            let mut recorder = Recorder::new(StdSink(std::vec::Vec::new())).unwrap();
+            recorder.record_frame(0, &TEST_FRAME).unwrap();
+            let mut bytes = recorder.into_inner().0;
+            let replayed = Replayer::new(&mut bytes).unwrap().next().unwrap().unwrap();
+            assert_eq!(replayed.record.payload, Payload::Frame(TEST_FRAME));
+        }
+
+        let mut garbage = [0x05, 0x01, 0x02, 0x03, 0x04, 0x00];
+        assert!(matches!(
+            Replayer::new(&mut garbage),
+            Err(ReplayError::MissingHeader) | Err(ReplayError::Decode(_))
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn replay_capture_in_chunks() {
+        use crate::capture::{CaptureError, CaptureReplay, Pacing};
+
+        let frame = [
+            0xAA, 0xAA, 0xDE, 0x01, 0x00, 0x92, 0xFF, 0x25, 0x08, 0x8D, 0xFE, 0xEC, 0xFF, 0xD1,
+            0x03, 0x00, 0x00, 0x00, 0xE7,
+        ];
+        let mut capture = std::vec::Vec::new();
+        capture.extend_from_slice(&[0x01, 0xAA, 0x02]);
+        capture.extend_from_slice(&frame);
+        capture.extend_from_slice(&frame[..9]);
+        capture.extend_from_slice(&frame);
+        capture.extend_from_slice(&[0x00; 4]);
+
+        static BB: BBBuffer<{ BUFFER_SIZE }> = BBBuffer::new();
+        let (mut processor, mut parser) = create(BB.borrow()).unwrap();
+        let stats = CaptureReplay::new(7, Pacing::LineRate)
+            .run(&capture, &mut processor, &mut parser, |f| {
+                assert_eq!(*f, TEST_FRAME)
+            })
+            .unwrap();
+        assert_eq!(stats.bytes, capture.len());
+        assert_eq!(stats.chunks, capture.len().div_ceil(7));
+        assert_eq!(stats.dropped_chunks, 0);
+        // The partial frame swallows the header of the next full one, which
+        // then fails its checksum: only the first frame is delivered.
+        assert_eq!(stats.frames, 1);
+
+        assert!(matches!(
+            CaptureReplay::new(7, Pacing::Unpaced).run_file(
+                "/nonexistent/capture.bin",
+                &mut processor,
+                &mut parser,
+                |_| {}
+            ),
+            Err(CaptureError::Io(_))
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn replay_capture_timed() {
+        use crate::capture::{CaptureReplay, Pacing};
+        use std::time::{Duration, Instant};
+
+        let frame = [
+            0xAA, 0xAA, 0xDE, 0x01, 0x00, 0x92, 0xFF, 0x25, 0x08, 0x8D, 0xFE, 0xEC, 0xFF, 0xD1,
+            0x03, 0x00, 0x00, 0x00, 0xE7,
+        ];
+        let capture = [
+            (5_000, &frame[..10]),
+            (5_900, &frame[10..]),
+            (25_000, &frame[..]),
+        ];
+
+        static BB: BBBuffer<{ BUFFER_SIZE }> = BBBuffer::new();
+        let (mut processor, mut parser) = create(BB.borrow()).unwrap();
+        let replay = CaptureReplay::new(4, Pacing::Unpaced);
+        let start = Instant::now();
+        let stats = replay
+            .run_timed(capture.iter().copied(), &mut processor, &mut parser, |f| {
+                assert_eq!(*f, TEST_FRAME)
+            })
+            .unwrap();
+        // The 20 ms gap between the two frames is kept.
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_eq!(stats.bytes, 2 * frame.len());
+        assert_eq!(stats.chunks, 3 + 3 + 5);
+        assert_eq!(stats.frames, 2);
+
+        #[cfg(feature = "recording")]
+        {
+            use crate::recording::{Recorder, StdSink};
+
+            let mut recorder = Recorder::new(StdSink(std::vec::Vec::new())).unwrap();
+            for (timestamp_us, bytes) in capture.iter() {
+                recorder.record_bytes(*timestamp_us, bytes).unwrap();
+            }
+            recorder.record_frame(30_000, &TEST_FRAME).unwrap();
+            let mut log = recorder.into_inner().0;
+            let start = Instant::now();
+            let stats = replay
+                .run_recording(&mut log, &mut processor, &mut parser, |f| {
+                    assert_eq!(*f, TEST_FRAME)
+                })
+                .unwrap();
+            assert!(start.elapsed() >= Duration::from_millis(20));
+            assert_eq!(stats.bytes, 2 * frame.len());
+            assert_eq!(stats.frames, 2);
+        }
+    }
+
+    #[cfg(all(feature = "std", feature = "float"))]
+    #[test]
+    fn csv_round_trip() {
+        use crate::csv::{CsvColumns, CsvError, CsvReader, CsvWriter};
+
+        let mut frame = TEST_FRAME.as_pretty_frame();
+        frame.motion_intent = 3;
+        let columns = CsvColumns {
+            diagnostics: true,
+            ..CsvColumns::default()
+        };
+        let mut writer = CsvWriter::new(std::vec::Vec::new(), columns);
+        writer.write_frame(Some(10_000), &frame).unwrap();
+        writer.write_frame(None, &frame).unwrap();
+        let text = std::string::String::from_utf8(writer.into_inner()).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next(),
+            Some("timestamp_us,index,yaw,pitch,roll,x_acc,y_acc,z_acc,motion_intent,motion_request,rsvd")
+        );
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("10000,222,0.01,-1.1,20.85,"));
+
+        let rows: std::vec::Vec<_> = CsvReader::new(text.as_bytes())
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].timestamp_us, Some(10_000));
+        assert_eq!(rows[0].frame, frame);
+        assert_eq!(rows[1].timestamp_us, None);
+
+        let edited = "z_acc,y_acc,x_acc,roll,pitch,yaw\n9.8,0,0,0,0,90\n1,2,x,4,5,6\n";
+        let mut reader = CsvReader::new(edited.as_bytes()).unwrap();
+        let row = reader.next().unwrap().unwrap();
+        assert_eq!(
+            (row.frame.index, row.frame.yaw, row.frame.z_acc),
+            (0, 90.0, 9.8)
+        );
+        assert!(matches!(
+            reader.next(),
+            Some(Err(CsvError::Parse {
+                line: 3,
+                column: "x_acc"
+            }))
+        ));
+        assert!(matches!(
+            CsvReader::new("yaw,pitch\n".as_bytes()),
+            Err(CsvError::MissingColumn("roll"))
+        ));
+
+        // Known columns after a dozen extra note columns.
+        let notes = "n,".repeat(12);
+        let wide = std::format!(
+            "{}yaw,pitch,roll,x_acc,y_acc,z_acc\n{}1,2,3,4,5,6\n",
+            notes,
+            notes
+        );
+        let row = CsvReader::new(wide.as_bytes())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!((row.frame.yaw, row.frame.z_acc), (1.0, 6.0));
+    }
+
+    #[cfg(feature = "mavlink")]
+    #[test]
+    fn mavlink_packets_decode() {
+        use crate::angular_velocity::AngularVelocity;
+        use crate::mavlink::*;
+        use crate::quaternion::Quaternion;
+        extern crate std;
+        use std::vec::Vec;
+
+        // Decoder independent of `crate::mavlink`: the message definitions
+        // of common.xml, with wire order and CRC_EXTRA derived as mavgen does.
+        type Fields = &'static [(&'static str, &'static str)];
+        const MESSAGES: [(u32, &str, Fields, Fields); 3] = [
+            (
+                30,
+                "ATTITUDE",
+                &[
+                    ("uint32_t", "time_boot_ms"),
+                    ("float", "roll"),
+                    ("float", "pitch"),
+                    ("float", "yaw"),
+                    ("float", "rollspeed"),
+                    ("float", "pitchspeed"),
+                    ("float", "yawspeed"),
+                ],
+                &[],
+            ),
+            (
+                31,
+                "ATTITUDE_QUATERNION",
+                &[
+                    ("uint32_t", "time_boot_ms"),
+                    ("float", "q1"),
+                    ("float", "q2"),
+                    ("float", "q3"),
+                    ("float", "q4"),
+                    ("float", "rollspeed"),
+                    ("float", "pitchspeed"),
+                    ("float", "yawspeed"),
+                ],
+                &[
+                    ("float", "repr_offset_q0"),
+                    ("float", "repr_offset_q1"),
+                    ("float", "repr_offset_q2"),
+                    ("float", "repr_offset_q3"),
+                ],
+            ),
+            (
+                26,
+                "SCALED_IMU",
+                &[
+                    ("uint32_t", "time_boot_ms"),
+                    ("int16_t", "xacc"),
+                    ("int16_t", "yacc"),
+                    ("int16_t", "zacc"),
+                    ("int16_t", "xgyro"),
+                    ("int16_t", "ygyro"),
+                    ("int16_t", "zgyro"),
+                    ("int16_t", "xmag"),
+                    ("int16_t", "ymag"),
+                    ("int16_t", "zmag"),
+                ],
+                &[("int16_t", "temperature")],
+            ),
+        ];
+
+        fn x25(bytes: &[u8], mut crc: u16) -> u16 {
+            for &b in bytes {
+                let t = b ^ crc as u8;
+                let t = (t ^ (t << 4)) as u16;
+                crc = (crc >> 8) ^ (t << 8) ^ (t << 3) ^ (t >> 4);
             }
+            crc
         }
+        fn size(ty: &str) -> usize {
+            if ty == "int16_t" {
+                2
+            } else {
+                4
+            }
+        }
+        fn wire_order(fields: Fields) -> Vec<(&'static str, &'static str)> {
+            let mut fields = fields.to_vec();
+            fields.sort_by_key(|(ty, _)| core::cmp::Reverse(size(ty)));
+            fields
+        }
+        fn crc_extra(name: &str, fields: Fields) -> u8 {
+            let mut crc = x25(name.as_bytes(), 0xFFFF);
+            crc = x25(b" ", crc);
+            for (ty, field) in wire_order(fields) {
+                for part in [ty, " ", field, " "] {
+                    crc = x25(part.as_bytes(), crc);
+                }
+            }
+            (crc & 0xFF) as u8 ^ (crc >> 8) as u8
+        }
+        // (msg id, sequence, fields by name)
+        fn decode(packet: &[u8]) -> (u32, u8, Vec<(&'static str, f64)>) {
+            assert_eq!(packet[0], 0xFD);
+            let len = packet[1] as usize;
+            assert_eq!(packet.len(), 10 + len + 2);
+            let msg_id = u32::from_le_bytes([packet[7], packet[8], packet[9], 0]);
+            let (_, name, base, extensions) = MESSAGES.iter().find(|m| m.0 == msg_id).unwrap();
+            let crc = x25(&packet[1..10 + len], 0xFFFF);
+            let crc = x25(&[crc_extra(name, base)], crc);
+            assert_eq!(&packet[10 + len..], &crc.to_le_bytes());
+
+            let mut payload = [0u8; 64];
+            payload[..len].copy_from_slice(&packet[10..10 + len]);
+            let mut offset = 0;
+            let mut values = Vec::new();
+            for (ty, field) in wire_order(base)
+                .into_iter()
+                .chain(extensions.iter().copied())
+            {
+                let b = &payload[offset..offset + size(ty)];
+                let value = match ty {
+                    "uint32_t" => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    "float" => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    _ => i16::from_le_bytes([b[0], b[1]]) as f64,
+                };
+                values.push((field, value));
+                offset += size(ty);
+            }
+            (msg_id, packet[4], values)
+        }
+        fn field(values: &[(&str, f64)], name: &str) -> f64 {
+            values.iter().find(|(n, _)| *n == name).unwrap().1
+        }
+
+        // Sanity checks of the decoder against published values.
+        assert_eq!(x25(b"123456789", 0xFFFF), 0x6F91);
+        let extras: Vec<u8> = MESSAGES
+            .iter()
+            .map(|(_, name, base, _)| crc_extra(name, base))
+            .collect();
+        assert_eq!(extras, [39, 246, 170]);
+
+        let mut frame = TEST_FRAME.as_pretty_frame();
+        frame.yaw = 30.0;
+        frame.pitch = 10.0;
+        frame.roll = -20.0;
+        let rate = AngularVelocity {
+            x: 0.1,
+            y: 0.2,
+            z: 0.3,
+        };
+        let mut encoder = MavlinkEncoder::new(1, 200);
+        let mut buf = [0u8; MAVLINK_V2_MAX_PACKET_LEN];
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-4;
+
+        // NED: clockwise yaw, nose-up pitch negated, FRD body rates.
+        let bytes = encoder
+            .attitude(1234, &frame, Some(rate), &mut buf)
+            .unwrap();
+        let (msg_id, sequence, values) = decode(bytes);
+        assert_eq!((msg_id, sequence, bytes[5], bytes[6]), (30, 0, 1, 200));
+        assert_eq!(field(&values, "time_boot_ms"), 1234.0);
+        assert!(close(field(&values, "roll"), (-20.0f64).to_radians()));
+        assert!(close(field(&values, "pitch"), (-10.0f64).to_radians()));
+        assert!(close(field(&values, "yaw"), (-30.0f64).to_radians()));
+        assert!(close(field(&values, "rollspeed"), 0.1));
+        assert!(close(field(&values, "pitchspeed"), -0.2));
+        assert!(close(field(&values, "yawspeed"), -0.3));
+
+        let bytes = encoder
+            .attitude_quaternion(1234, &frame, None, &mut buf)
+            .unwrap();
+        // Zero rates are truncated off the end of the payload.
+        assert_eq!(bytes.len(), MAVLINK_V2_HEADER_LEN + 20 + 2);
+        let (msg_id, sequence, values) = decode(bytes);
+        assert_eq!((msg_id, sequence), (31, 1));
+        let q = Quaternion::from_euler_degrees(-30.0, -10.0, -20.0);
+        let q = [q.w, q.x, q.y, q.z];
+        let sign = field(&values, "q1").signum() * (q[0] as f64).signum();
+        for (name, expected) in ["q1", "q2", "q3", "q4"].iter().zip(q.iter()) {
+            assert!(close(field(&values, name), sign * *expected as f64));
+        }
+        assert_eq!(field(&values, "yawspeed"), 0.0);
+
+        frame.x_acc = 0.0;
+        frame.y_acc = 0.0;
+        frame.z_acc = 9.80665;
+        let bytes = encoder.scaled_imu(5, &frame, Some(rate), &mut buf).unwrap();
+        let (msg_id, _, values) = decode(bytes);
+        assert_eq!(msg_id, 26);
+        assert_eq!(field(&values, "zacc"), -1000.0);
+        assert_eq!(field(&values, "xgyro"), 100.0);
+        assert_eq!(field(&values, "ygyro"), -200.0);
+        assert_eq!(field(&values, "zgyro"), -300.0);
+        assert_eq!(field(&values, "temperature"), 0.0);
+
+        assert_eq!(
+            encoder.attitude(0, &frame, None, &mut buf[..10]),
+            Err(MavlinkError::BufferTooSmall)
+        );
+    }
+
+    #[cfg(feature = "ros")]
+    #[test]
+    fn ros_imu_serialisation() {
+        use crate::ros::*;
+
+        let f64_at = |buf: &[u8], offset: usize| {
+            let mut b = [0u8; 8];
+            b.copy_from_slice(&buf[offset..offset + 8]);
+            f64::from_le_bytes(b)
+        };
+
+        let mut frame = TEST_FRAME.as_pretty_frame();
+        frame.yaw = 0.0;
+        frame.pitch = 0.0;
+        frame.roll = 0.0;
+        let config = RosImuConfig::default();
+        let imu = frame.to_ros_imu(&config, RosTime::from_micros(1_500_000), "imu");
+
+        // Zero sensor yaw faces north, i.e. 90° in ENU.
+        let half = core::f64::consts::FRAC_1_SQRT_2;
+        assert!((imu.orientation[2] - half).abs() < 1e-6);
+        assert!((imu.orientation[3] - half).abs() < 1e-6);
+        assert_eq!(imu.angular_velocity_covariance[0], -1.0);
+        assert_eq!(imu.linear_acceleration[2], frame.z_acc as f64);
+        assert_eq!(
+            imu.stamp,
+            RosTime {
+                sec: 1,
+                nanosec: 500_000_000
+            }
+        );
+
+        // ROS 1: seq, stamp, "imu", then 4 + 3 * 2 + 9 * 3 f64s.
+        let mut buf = [0u8; 512];
+        let len = imu.to_ros1(7, &mut buf).unwrap();
+        assert_eq!(len, 12 + 4 + 3 + 37 * 8);
+        assert_eq!(&buf[..4], &7u32.to_le_bytes());
+        assert_eq!(&buf[16..19], b"imu");
+        assert!((f64_at(&buf, 19 + 16) - half).abs() < 1e-6);
+        assert_eq!(f64_at(&buf, 19 + 16 * 8), -1.0);
+
+        // CDR: header, stamp, "imu\0", f64s aligned to 8 from byte 20.
+        let len = imu.to_cdr(&mut buf).unwrap();
+        assert_eq!(&buf[..4], &[0, 1, 0, 0]);
+        assert_eq!(&buf[12..16], &4u32.to_le_bytes());
+        assert_eq!(&buf[16..20], b"imu\0");
+        assert_eq!(len, 20 + 37 * 8);
+        assert!((f64_at(&buf, 20 + 16) - half).abs() < 1e-6);
+        assert_eq!(f64_at(&buf, 20 + 16 * 8), -1.0);
+        assert_eq!(f64_at(&buf, 20 + 4 * 8), config.orientation_covariance[0]);
+
+        assert_eq!(imu.to_cdr(&mut buf[..100]), Err(RosError::BufferTooSmall));
+    }
+
+    #[cfg(feature = "nmea")]
+    #[test]
+    fn nmea_sentences_round_trip() {
+        use crate::nmea::*;
+
+        let mut frame = TEST_FRAME.as_pretty_frame();
+        frame.yaw = 30.0;
+        let mut buf = [0u8; NMEA_MAX_SENTENCE_LEN];
+
+        let len = frame.write_prvc(&mut buf).unwrap();
+        let sentence = core::str::from_utf8(&buf[..len]).unwrap();
+        assert!(sentence.starts_with("$PRVC,30.00,"));
+        assert!(sentence.ends_with("\r\n"));
+        match parse_sentence(sentence).unwrap() {
+            NmeaSentence::Rvc(rvc) => {
+                let parsed = rvc.to_pretty_frame(frame.index);
+                assert_eq!(parsed.yaw, 30.0);
+                assert!((parsed.pitch - frame.pitch).abs() <= 0.005);
+                assert!((parsed.roll - frame.roll).abs() <= 0.005);
+                assert!((parsed.z_acc - frame.z_acc).abs() <= 0.0005);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Counter-clockwise yaw of 30° is a heading of 330°.
+        let len = frame.write_hdt(&mut buf).unwrap();
+        let sentence = core::str::from_utf8(&buf[..len]).unwrap();
+        assert_eq!(sentence, "$HCHDT,330.0,T*29\r\n");
+        assert_eq!(parse_sentence(sentence), Ok(NmeaSentence::Heading(330.0)));
+        frame.yaw = 0.01;
+        let len = frame.write_hdt(&mut buf).unwrap();
+        assert!(buf[..len].starts_with(b"$HCHDT,0.0,T*"));
+
+        assert_eq!(
+            parse_sentence("$GPHDT,12.5,T*03"),
+            Ok(NmeaSentence::Heading(12.5))
+        );
+        assert_eq!(
+            parse_sentence("$HCHDT,330.0,T*2E"),
+            Err(NmeaError::BadChecksum {
+                expected: 0x29,
+                found: 0x2E
+            })
+        );
+        assert_eq!(
+            parse_sentence("HCHDT,330.0,T*29"),
+            Err(NmeaError::MissingStart)
+        );
+        assert_eq!(
+            parse_sentence("$HCHDT,330.0,T"),
+            Err(NmeaError::MissingChecksum)
+        );
+        assert_eq!(parse_sentence("$PRVC,1,2*14"), Err(NmeaError::BadField(2)));
+        assert_eq!(
+            frame.write_prvc(&mut buf[..20]),
+            Err(NmeaError::BufferTooSmall)
+        );
+    }
+
+    #[cfg(feature = "can")]
+    #[test]
+    fn can_frames_round_trip() {
+        use crate::can::*;
+        use embedded_can::{ExtendedId, Frame, Id, StandardId};
+
+        let mut encoder = CanEncoder::default();
+        let mut decoder = CanDecoder::default();
+
+        let [orientation, acceleration]: [CanFrame; 2] = encoder.encode(&TEST_FRAME).unwrap();
+        assert_eq!(
+            orientation.id(),
+            Id::Standard(StandardId::new(0x510).unwrap())
+        );
+        assert_eq!(orientation.dlc(), 8);
+        assert_eq!(&orientation.data()[..2], &TEST_FRAME.yaw.to_le_bytes());
+        assert_eq!(&acceleration.data()[4..6], &TEST_FRAME.z_acc.to_le_bytes());
+        assert_eq!(
+            orientation.data()[7],
+            can_checksum(orientation.id(), &orientation.data()[..7])
+        );
+
+        assert_eq!(decoder.push(&orientation), Ok(None));
+        let decoded = decoder.push(&acceleration).unwrap().unwrap();
+        assert_eq!(
+            (decoded.yaw, decoded.pitch, decoded.roll),
+            (TEST_FRAME.yaw, TEST_FRAME.pitch, TEST_FRAME.roll)
+        );
+        assert_eq!(
+            (decoded.x_acc, decoded.y_acc, decoded.z_acc),
+            (TEST_FRAME.x_acc, TEST_FRAME.y_acc, TEST_FRAME.z_acc)
+        );
+        assert_eq!(decoded.index, 0);
+
+        // A corrupted byte fails the checksum.
+        let [orientation, acceleration]: [CanFrame; 2] = encoder.encode(&TEST_FRAME).unwrap();
+        let mut corrupted = orientation;
+        corrupted.data[0] ^= 1;
+        assert_eq!(decoder.push(&corrupted), Err(CanError::BadChecksum));
+        assert_eq!(decoder.push(&acceleration), Err(CanError::CounterMismatch));
+
+        // Sample 1 was lost, sample 2 decodes and is counted as a gap.
+        let [orientation, acceleration]: [CanFrame; 2] = encoder.encode(&TEST_FRAME).unwrap();
+        decoder.push(&orientation).unwrap();
+        assert_eq!(decoder.push(&acceleration).unwrap().unwrap().index, 2);
+        assert_eq!(decoder.lost_samples(), 1);
+
+        let other = CanFrame::new(StandardId::new(0x100).unwrap(), &[0; 8]).unwrap();
+        assert_eq!(decoder.push(&other), Err(CanError::UnexpectedId));
+
+        // A frame type that cannot carry the configured ids is reported.
+        struct StandardOnly(CanFrame);
+        impl Frame for StandardOnly {
+            fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+                match id.into() {
+                    Id::Standard(id) => CanFrame::new(id, data).map(StandardOnly),
+                    Id::Extended(_) => None,
+                }
+            }
+            fn new_remote(_id: impl Into<Id>, _dlc: usize) -> Option<Self> {
+                None
+            }
+            fn is_extended(&self) -> bool {
+                false
+            }
+            fn is_remote_frame(&self) -> bool {
+                false
+            }
+            fn id(&self) -> Id {
+                self.0.id()
+            }
+            fn dlc(&self) -> usize {
+                self.0.dlc()
+            }
+            fn data(&self) -> &[u8] {
+                self.0.data()
+            }
+        }
+        let mut encoder = CanEncoder::with_extended_ids(
+            ExtendedId::new(0x18FF_0010).unwrap(),
+            ExtendedId::new(0x18FF_0011).unwrap(),
+        );
+        assert!(matches!(
+            encoder.encode::<StandardOnly>(&TEST_FRAME),
+            Err(CanError::FrameRejected)
+        ));
+    }
+
+    #[test]
+    fn telemetry_delta_round_trip() {
+        use crate::telemetry::*;
+
+        let frames: [Bno08xRvcRawFrame; 12] = core::array::from_fn(|i| Bno08xRvcRawFrame {
+            index: TEST_FRAME.index.wrapping_add(i as u8),
+            yaw: 17_990 + 3 * i as i16,
+            x_acc: TEST_FRAME.x_acc - i as i16,
+            csum: 0,
+            ..TEST_FRAME
+        });
+        let config = TelemetryConfig {
+            decimation: 2,
+            keyframe_interval: 3,
+        };
+        let mut encoder = TelemetryEncoder::new(config);
+        let mut packets = [([0u8; TELEMETRY_MAX_PACKET_LEN], 0usize); 6];
+        let mut sent = 0;
+        for frame in frames.iter() {
+            let mut buf = [0u8; TELEMETRY_MAX_PACKET_LEN];
+            if let Some(len) = encoder.encode(frame, &mut buf).unwrap() {
+                packets[sent] = (buf, len);
+                sent += 1;
+            }
+        }
+        // Every second frame, keyframes at packets 0 and 3.
+        assert_eq!(sent, 6);
+        assert_eq!(packets[0].0[0], 1);
+        assert_eq!(packets[1].0[0], 0);
+        assert_eq!(packets[3].0[0], 1);
+        // Yaw +6, x_acc -2, index +2: flags, sequence, mask, 3 varints.
+        assert_eq!(packets[1].1, 6);
+        assert!(packets[0].1 < core::mem::size_of::<Bno08xRvcRawFrame>() + 3);
+
+        let mut decoder = TelemetryDecoder::new();
+        for (i, (buf, len)) in packets.iter().enumerate() {
+            assert_eq!(decoder.decode(&buf[..*len]), Ok(frames[2 * i]));
+        }
+
+        // Packet 1 lost: packet 2 is dropped, packet 3 is a keyframe.
+        let mut decoder = TelemetryDecoder::new();
+        decoder.decode(&packets[0].0[..packets[0].1]).unwrap();
+        assert_eq!(
+            decoder.decode(&packets[2].0[..packets[2].1]),
+            Err(TelemetryError::MissingKeyframe)
+        );
+        assert_eq!(decoder.decode(&packets[3].0[..packets[3].1]), Ok(frames[6]));
+        assert_eq!(decoder.decode(&packets[4].0[..packets[4].1]), Ok(frames[8]));
+        assert_eq!(decoder.lost_packets(), 1);
+
+        // A corrupted delta is not used as the base of the next one.
+        assert_eq!(
+            decoder.decode(&packets[5].0[..packets[5].1 - 1]),
+            Err(TelemetryError::Truncated)
+        );
+
+        // Duplicated and reordered packets are ignored without losing the
+        // reference or counting a gap.
+        let packet = |i: usize| &packets[i].0[..packets[i].1];
+        let mut decoder = TelemetryDecoder::new();
+        decoder.decode(packet(0)).unwrap();
+        decoder.decode(packet(1)).unwrap();
+        assert_eq!(decoder.decode(packet(1)), Err(TelemetryError::StalePacket));
+        assert_eq!(decoder.decode(packet(0)), Err(TelemetryError::StalePacket));
+        assert_eq!(decoder.decode(packet(2)), Ok(frames[4]));
+        assert_eq!(decoder.lost_packets(), 0);
+
+        // Gaps count even without a reference; a sender that jumped back is
+        // followed after a few stale packets.
+        let mut decoder = TelemetryDecoder::new();
+        assert_eq!(
+            decoder.decode(packet(1)),
+            Err(TelemetryError::MissingKeyframe)
+        );
+        assert_eq!(decoder.decode(packet(3)), Ok(frames[6]));
+        assert_eq!(decoder.lost_packets(), 1);
+        for _ in 0..3 {
+            assert_eq!(decoder.decode(packet(0)), Err(TelemetryError::StalePacket));
+        }
+        assert_eq!(decoder.decode(packet(0)), Ok(frames[0]));
+        assert_eq!(decoder.decode(packet(1)), Ok(frames[2]));
+        assert_eq!(decoder.lost_packets(), 1);
+        let mut encoder = TelemetryEncoder::new(TelemetryConfig::default());
+        let mut decoder = TelemetryDecoder::new();
+        let mut buf = [0u8; TELEMETRY_MAX_PACKET_LEN];
+        for (yaw, expected) in [
+            (0, Ok(0)),
+            (100, Err(TelemetryError::Truncated)),
+            (200, Err(TelemetryError::MissingKeyframe)),
+        ] {
+            let frame = Bno08xRvcRawFrame { yaw, ..frames[0] };
+            let mut len = encoder.encode(&frame, &mut buf).unwrap().unwrap();
+            if yaw == 100 {
+                len -= 1;
+            }
+            let decoded = decoder.decode(&buf[..len]).map(|frame| frame.yaw);
+            assert_eq!(decoded, expected);
+        }
+        assert_eq!(
+            TelemetryEncoder::new(config).encode(&frames[0], &mut [0u8; 4]),
+            Err(TelemetryError::BufferTooSmall)
+        );
+    }
+
+    #[cfg(all(feature = "std", feature = "float"))]
+    #[test]
+    fn influx_and_json_lines_export() {
+        use crate::export::{FieldValue, InfluxWriter, JsonLinesWriter, LineRecord};
+        use crate::tilt::TiltAlarmEvent;
+        use crate::validation::InvalidReason;
+        use crate::vibration::VibrationStats;
+
+        let mut frame = TEST_FRAME.as_pretty_frame();
+        frame.yaw = 4.78;
+        frame.pitch = -1.1;
+        frame.roll = 0.0;
+        frame.x_acc = 0.5;
+        frame.y_acc = f32::NAN;
+        frame.z_acc = 9.75;
+
+        let mut influx = InfluxWriter::new(std::vec::Vec::new())
+            .with_tag("sensor", "imu 0")
+            .with_tag("site", "a,b");
+        influx.write(Some(1_000_000_000), &frame).unwrap();
+        influx.write(Some(2_000), &TiltAlarmEvent::Raised).unwrap();
+        influx.write(None, &InvalidReason::OrientationJump).unwrap();
+        influx.write(Some(3), &TEST_FRAME).unwrap();
+        let text = std::string::String::from_utf8(influx.into_inner()).unwrap();
+        let lines: std::vec::Vec<_> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "rvc_frame,sensor=imu\\ 0,site=a\\,b index=222i,yaw=4.78,pitch=-1.1,roll=0,\
+             x_acc=0.5,z_acc=9.75,motion_intent=0i,motion_request=0i 1000000000"
+        );
+        assert_eq!(
+            lines[1],
+            "rvc_tilt_alarm,sensor=imu\\ 0,site=a\\,b event=\"raised\" 2000"
+        );
+        assert_eq!(
+            lines[2],
+            "rvc_invalid_frame,sensor=imu\\ 0,site=a\\,b reason=\"orientation_jump\""
+        );
+        assert!(lines[3].starts_with("rvc_raw_frame,sensor=imu\\ 0,site=a\\,b index=222i,yaw=1i,"));
+
+        struct Custom;
+        impl LineRecord for Custom {
+            fn measurement(&self) -> &'static str {
+                "a=b c,d"
+            }
+            fn fields(&self) -> std::vec::Vec<(&'static str, FieldValue)> {
+                std::vec![("x", FieldValue::Int(1))]
+            }
+        }
+        let mut influx = InfluxWriter::new(std::vec::Vec::new())
+            .with_tag("", "x")
+            .with_tag("empty", "")
+            .with_tag("k=1", "v=2");
+        influx.write(None, &Custom).unwrap();
+        let text = std::string::String::from_utf8(influx.into_inner()).unwrap();
+        assert_eq!(text, "a=b\\ c\\,d,k\\=1=v\\=2 x=1i\n");
+
+        let mut json = JsonLinesWriter::new(std::vec::Vec::new()).with_tag("sensor", "imu\"0");
+        json.write(Some(5), &frame).unwrap();
+        json.write(
+            None,
+            &VibrationStats {
+                rms: 0.25,
+                peak: 1.0,
+                crest_factor: 4.0,
+            },
+        )
+        .unwrap();
+        let text = std::string::String::from_utf8(json.into_inner()).unwrap();
+        let lines: std::vec::Vec<_> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "{\"measurement\":\"rvc_frame\",\"timestamp_ns\":5,\"tags\":{\"sensor\":\"imu\\\"0\"},\
+             \"fields\":{\"index\":222,\"yaw\":4.78,\"pitch\":-1.1,\"roll\":0,\"x_acc\":0.5,\
+             \"y_acc\":null,\"z_acc\":9.75,\"motion_intent\":0,\"motion_request\":0}}"
+        );
+        assert_eq!(
+            lines[1],
+            "{\"measurement\":\"rvc_vibration\",\"tags\":{\"sensor\":\"imu\\\"0\"},\
+             \"fields\":{\"rms\":0.25,\"peak\":1,\"crest_factor\":4}}"
+        );
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn units_conversions() {
+        let frame = TEST_FRAME.as_pretty_frame();
+        assert!((frame.roll_angle().radians() - 0.363_901).abs() < 0.0001);
+        assert!((frame.z_acceleration().g() - 0.977).abs() < 0.0001);
+        assert!((frame.x_acceleration().milli_g() + 371.0).abs() < 0.01);
+    }
+
+    #[cfg(feature = "fixed")]
+    #[test]
+    fn fixed_output_test() {
+        let frame = TEST_FRAME.as_fixed_frame();
+        assert_eq!(frame.index, 222);
+        assert!((frame.yaw.to_num::<f32>() - 0.01).abs() < 0.001);
+        assert!((frame.pitch.to_num::<f32>() + 1.10).abs() < 0.001);
+        assert!((frame.roll.to_num::<f32>() - 20.85).abs() < 0.001);
+        assert!((frame.x_acc.to_num::<f32>() + 3.638).abs() < 0.001);
+        assert!((frame.y_acc.to_num::<f32>() + 0.196).abs() < 0.001);
+        assert!((frame.z_acc.to_num::<f32>() - 9.581).abs() < 0.001);
     }
 
+    #[cfg(feature = "float")]
     #[test]
     fn pretty_output_test() {
         const P_FRAME: Bno08xRvcPrettyFrame = Bno08xRvcPrettyFrame {
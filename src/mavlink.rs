@@ -0,0 +1,200 @@
+//! MAVLink v2 `ATTITUDE`, `ATTITUDE_QUATERNION` and `SCALED_IMU` messages.
+//!
+//! All values follow the MAVLink convention: NED world, FRD body axes (see
+//! `convention`), angles in radians, accelerations in mG, rates in rad/s
+//! (mrad/s for `SCALED_IMU`). RVC mode has no gyro, so rates come from an
+//! optional `AngularVelocity` estimate and are sent as 0 without one.
+//! Packets are unsigned, with trailing zero payload bytes truncated as the
+//! v2 spec requires.
+
+use crate::angular_velocity::AngularVelocity;
+use crate::convention::FrameConvention;
+use crate::parser::Bno08xRvcPrettyFrame;
+use crate::units::G_ACCELERATION;
+
+pub const MAVLINK_V2_STX: u8 = 0xFD;
+pub const MAVLINK_V2_HEADER_LEN: usize = 10;
+pub const MAVLINK_V2_MAX_PACKET_LEN: usize = MAVLINK_V2_HEADER_LEN + 255 + 2;
+
+pub const MSG_ID_SCALED_IMU: u32 = 26;
+pub const MSG_ID_ATTITUDE: u32 = 30;
+pub const MSG_ID_ATTITUDE_QUATERNION: u32 = 31;
+
+const CRC_EXTRA_SCALED_IMU: u8 = 170;
+const CRC_EXTRA_ATTITUDE: u8 = 39;
+const CRC_EXTRA_ATTITUDE_QUATERNION: u8 = 246;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MavlinkError {
+    BufferTooSmall,
+}
+
+// CRC-16/MCRF4XX ("X.25") as used by MAVLink.
+fn crc_x25(bytes: &[u8], mut crc: u16) -> u16 {
+    for b in bytes {
+        let mut tmp = *b ^ (crc as u8);
+        tmp ^= tmp << 4;
+        crc = (crc >> 8) ^ ((tmp as u16) << 8) ^ ((tmp as u16) << 3) ^ ((tmp as u16) >> 4);
+    }
+    crc
+}
+
+fn crc_extra(msg_id: u32) -> u8 {
+    match msg_id {
+        MSG_ID_SCALED_IMU => CRC_EXTRA_SCALED_IMU,
+        MSG_ID_ATTITUDE => CRC_EXTRA_ATTITUDE,
+        _ => CRC_EXTRA_ATTITUDE_QUATERNION,
+    }
+}
+
+struct Payload {
+    buf: [u8; 64],
+    len: usize,
+}
+
+impl Payload {
+    fn new() -> Self {
+        Payload {
+            buf: [0; 64],
+            len: 0,
+        }
+    }
+
+    fn put(&mut self, bytes: &[u8]) {
+        self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.put(&v.to_le_bytes());
+    }
+
+    fn f32(&mut self, v: f32) {
+        self.put(&v.to_le_bytes());
+    }
+
+    fn i16(&mut self, v: i16) {
+        self.put(&v.to_le_bytes());
+    }
+}
+
+fn saturate_i16(v: f32) -> i16 {
+    libm::roundf(v).clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
+
+// FLU body rates to FRD.
+fn frd_rates(rate: Option<AngularVelocity>) -> [f32; 3] {
+    match rate {
+        None => [0.0; 3],
+        Some(r) => [r.x, -r.y, -r.z],
+    }
+}
+
+/// Builds MAVLink v2 packets with a running sequence number.
+pub struct MavlinkEncoder {
+    system_id: u8,
+    component_id: u8,
+    sequence: u8,
+}
+
+impl MavlinkEncoder {
+    pub fn new(system_id: u8, component_id: u8) -> Self {
+        MavlinkEncoder {
+            system_id,
+            component_id,
+            sequence: 0,
+        }
+    }
+
+    pub fn attitude<'b>(
+        &mut self,
+        time_boot_ms: u32,
+        frame: &Bno08xRvcPrettyFrame,
+        rate: Option<AngularVelocity>,
+        buf: &'b mut [u8],
+    ) -> Result<&'b [u8], MavlinkError> {
+        let ned = frame.to_convention(FrameConvention::Ned);
+        let rates = frd_rates(rate);
+        let mut p = Payload::new();
+        p.u32(time_boot_ms);
+        p.f32(ned.roll.to_radians());
+        p.f32(ned.pitch.to_radians());
+        p.f32(ned.yaw.to_radians());
+        rates.iter().for_each(|r| p.f32(*r));
+        self.finish(MSG_ID_ATTITUDE, &p, buf)
+    }
+
+    pub fn attitude_quaternion<'b>(
+        &mut self,
+        time_boot_ms: u32,
+        frame: &Bno08xRvcPrettyFrame,
+        rate: Option<AngularVelocity>,
+        buf: &'b mut [u8],
+    ) -> Result<&'b [u8], MavlinkError> {
+        let q = frame.to_convention(FrameConvention::Ned).orientation;
+        let rates = frd_rates(rate);
+        let mut p = Payload::new();
+        p.u32(time_boot_ms);
+        [q.w, q.x, q.y, q.z].iter().for_each(|v| p.f32(*v));
+        rates.iter().for_each(|r| p.f32(*r));
+        self.finish(MSG_ID_ATTITUDE_QUATERNION, &p, buf)
+    }
+
+    /// `SCALED_IMU` with accelerations and, if given, estimated rates. The
+    /// magnetometer fields are 0 and the temperature is reported as not
+    /// available.
+    pub fn scaled_imu<'b>(
+        &mut self,
+        time_boot_ms: u32,
+        frame: &Bno08xRvcPrettyFrame,
+        rate: Option<AngularVelocity>,
+        buf: &'b mut [u8],
+    ) -> Result<&'b [u8], MavlinkError> {
+        let acc = frame.to_convention(FrameConvention::Ned).acc;
+        let rates = frd_rates(rate);
+        let mut p = Payload::new();
+        p.u32(time_boot_ms);
+        acc.iter()
+            .for_each(|a| p.i16(saturate_i16(a * 1000.0 / G_ACCELERATION)));
+        rates.iter().for_each(|r| p.i16(saturate_i16(r * 1000.0)));
+        [0i16; 3].iter().for_each(|m| p.i16(*m));
+        p.i16(0);
+        self.finish(MSG_ID_SCALED_IMU, &p, buf)
+    }
+
+    fn finish<'b>(
+        &mut self,
+        msg_id: u32,
+        payload: &Payload,
+        buf: &'b mut [u8],
+    ) -> Result<&'b [u8], MavlinkError> {
+        let mut len = payload.len;
+        while len > 1 && payload.buf[len - 1] == 0 {
+            len -= 1;
+        }
+        let total = MAVLINK_V2_HEADER_LEN + len + 2;
+        if buf.len() < total {
+            return Err(MavlinkError::BufferTooSmall);
+        }
+        let id = msg_id.to_le_bytes();
+        buf[..MAVLINK_V2_HEADER_LEN].copy_from_slice(&[
+            MAVLINK_V2_STX,
+            len as u8,
+            0, // incompat flags
+            0, // compat flags
+            self.sequence,
+            self.system_id,
+            self.component_id,
+            id[0],
+            id[1],
+            id[2],
+        ]);
+        buf[MAVLINK_V2_HEADER_LEN..MAVLINK_V2_HEADER_LEN + len]
+            .copy_from_slice(&payload.buf[..len]);
+        let crc = crc_x25(&buf[1..MAVLINK_V2_HEADER_LEN + len], 0xFFFF);
+        let crc = crc_x25(&[crc_extra(msg_id)], crc);
+        buf[MAVLINK_V2_HEADER_LEN + len..total].copy_from_slice(&crc.to_le_bytes());
+        self.sequence = self.sequence.wrapping_add(1);
+        Ok(&buf[..total])
+    }
+}
@@ -0,0 +1,115 @@
+/// Which member of the BNO08x family is producing the RVC stream.
+///
+/// Only the BNO086 fills the motion intent / motion request bytes; on the
+/// other parts they are reserved and must not be interpreted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SensorVariant {
+    Bno080,
+    Bno085,
+    Bno086,
+}
+
+impl SensorVariant {
+    pub fn reports_motion(&self) -> bool {
+        matches!(self, SensorVariant::Bno086)
+    }
+}
+
+/// BNO086 motion intent (MI) byte.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MotionIntent {
+    Indeterminate,              // 0
+    StationaryWithoutVibration, // 1
+    StationaryWithVibration,    // 2
+    InMotion,                   // 3
+    InMotionAccelerating,       // 4
+    Unknown(u8),
+}
+
+impl MotionIntent {
+    pub fn is_stationary(&self) -> bool {
+        matches!(
+            self,
+            MotionIntent::StationaryWithoutVibration | MotionIntent::StationaryWithVibration
+        )
+    }
+}
+
+impl From<u8> for MotionIntent {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => MotionIntent::Indeterminate,
+            1 => MotionIntent::StationaryWithoutVibration,
+            2 => MotionIntent::StationaryWithVibration,
+            3 => MotionIntent::InMotion,
+            4 => MotionIntent::InMotionAccelerating,
+            v => MotionIntent::Unknown(v),
+        }
+    }
+}
+
+impl From<MotionIntent> for u8 {
+    fn from(value: MotionIntent) -> Self {
+        match value {
+            MotionIntent::Indeterminate => 0,
+            MotionIntent::StationaryWithoutVibration => 1,
+            MotionIntent::StationaryWithVibration => 2,
+            MotionIntent::InMotion => 3,
+            MotionIntent::InMotionAccelerating => 4,
+            MotionIntent::Unknown(v) => v,
+        }
+    }
+}
+
+/// BNO086 motion request (MR) byte.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MotionRequest {
+    NoConstraint,           // 0
+    StayStationary,         // 1
+    NonUrgentStabilization, // 4, stabilize and stay stationary
+    UrgentStabilization,    // 5, stabilize and stay stationary
+    Unknown(u8),
+}
+
+impl From<u8> for MotionRequest {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => MotionRequest::NoConstraint,
+            1 => MotionRequest::StayStationary,
+            4 => MotionRequest::NonUrgentStabilization,
+            5 => MotionRequest::UrgentStabilization,
+            v => MotionRequest::Unknown(v),
+        }
+    }
+}
+
+impl From<MotionRequest> for u8 {
+    fn from(value: MotionRequest) -> Self {
+        match value {
+            MotionRequest::NoConstraint => 0,
+            MotionRequest::StayStationary => 1,
+            MotionRequest::NonUrgentStabilization => 4,
+            MotionRequest::UrgentStabilization => 5,
+            MotionRequest::Unknown(v) => v,
+        }
+    }
+}
+
+pub(crate) fn decode_intent(variant: SensorVariant, raw: u8) -> Option<MotionIntent> {
+    if variant.reports_motion() {
+        Some(MotionIntent::from(raw))
+    } else {
+        None
+    }
+}
+
+pub(crate) fn decode_request(variant: SensorVariant, raw: u8) -> Option<MotionRequest> {
+    if variant.reports_motion() {
+        Some(MotionRequest::from(raw))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,175 @@
+//! NMEA 0183 sentences: proprietary `$PRVC` and standard `$HCHDT`.
+//!
+//! `$PRVC,yaw,pitch,roll,ax,ay,az*CS` carries the frame in the sensor
+//! convention (degrees with 0.01° resolution, m/s2 with 3 decimals).
+//! `$HCHDT,heading,T*CS` carries the heading clockwise from the reference
+//! direction in [0, 360). Both are terminated with `\r\n`.
+
+use core::fmt::Write;
+
+use crate::parser::Bno08xRvcPrettyFrame;
+use crate::units::wrap_degrees;
+
+/// Longest sentence allowed by NMEA 0183, including `\r\n`.
+pub const NMEA_MAX_SENTENCE_LEN: usize = 82;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NmeaError {
+    BufferTooSmall,
+    MissingStart,
+    MissingChecksum,
+    BadChecksum {
+        expected: u8,
+        found: u8,
+    },
+    UnknownSentence,
+    /// The field at this position (0 is the first after the address) is
+    /// missing or not a number.
+    BadField(usize),
+}
+
+/// A parsed `$PRVC` sentence.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RvcSentence {
+    pub yaw: f32,      // degrees
+    pub pitch: f32,    // degrees
+    pub roll: f32,     // degrees
+    pub acc: [f32; 3], // m/s2
+}
+
+impl RvcSentence {
+    /// Pretty frame with the given index and zeroed BNO086 fields.
+    pub fn to_pretty_frame(&self, index: u8) -> Bno08xRvcPrettyFrame {
+        Bno08xRvcPrettyFrame {
+            index,
+            yaw: self.yaw,
+            pitch: self.pitch,
+            roll: self.roll,
+            x_acc: self.acc[0],
+            y_acc: self.acc[1],
+            z_acc: self.acc[2],
+            motion_intent: 0,
+            motion_request: 0,
+            rsvd: 0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NmeaSentence {
+    Rvc(RvcSentence),
+    /// True heading in degrees, from `$--HDT` of any talker.
+    Heading(f32),
+}
+
+/// XOR of all bytes between `$` and `*`.
+pub fn nmea_checksum(body: &[u8]) -> u8 {
+    body.iter().fold(0, |cs, b| cs ^ b)
+}
+
+struct SliceWriter<'b> {
+    buf: &'b mut [u8],
+    pos: usize,
+}
+
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.pos + s.len();
+        self.buf
+            .get_mut(self.pos..end)
+            .ok_or(core::fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.pos = end;
+        Ok(())
+    }
+}
+
+fn finish_sentence(
+    buf: &mut [u8],
+    body: impl FnOnce(&mut SliceWriter) -> core::fmt::Result,
+) -> Result<usize, NmeaError> {
+    let mut w = SliceWriter { buf, pos: 0 };
+    w.write_str("$").map_err(|_| NmeaError::BufferTooSmall)?;
+    body(&mut w).map_err(|_| NmeaError::BufferTooSmall)?;
+    let cs = nmea_checksum(&w.buf[1..w.pos]);
+    write!(w, "*{:02X}\r\n", cs).map_err(|_| NmeaError::BufferTooSmall)?;
+    Ok(w.pos)
+}
+
+/// Heading clockwise from the reference direction, in [0, 360).
+pub fn true_heading(frame: &Bno08xRvcPrettyFrame) -> f32 {
+    let heading = -wrap_degrees(frame.yaw);
+    if heading < 0.0 {
+        heading + 360.0
+    } else {
+        heading
+    }
+}
+
+impl Bno08xRvcPrettyFrame {
+    /// Writes `$PRVC,...*CS\r\n` into `buf`, returning its length.
+    pub fn write_prvc(&self, buf: &mut [u8]) -> Result<usize, NmeaError> {
+        finish_sentence(buf, |w| {
+            write!(
+                w,
+                "PRVC,{:.2},{:.2},{:.2},{:.3},{:.3},{:.3}",
+                self.yaw, self.pitch, self.roll, self.x_acc, self.y_acc, self.z_acc
+            )
+        })
+    }
+
+    /// Writes `$HCHDT,<heading>,T*CS\r\n` into `buf`, returning its length.
+    pub fn write_hdt(&self, buf: &mut [u8]) -> Result<usize, NmeaError> {
+        // Avoid printing 360.0 for headings just below it.
+        let mut heading = libm::roundf(true_heading(self) * 10.0) / 10.0;
+        if heading >= 360.0 {
+            heading = 0.0;
+        }
+        finish_sentence(buf, |w| write!(w, "HCHDT,{:.1},T", heading))
+    }
+}
+
+fn parse_hex(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Parses one sentence, with or without the trailing `\r\n`.
+pub fn parse_sentence(sentence: &str) -> Result<NmeaSentence, NmeaError> {
+    let sentence = sentence.trim_end_matches(['\r', '\n']);
+    let rest = sentence.strip_prefix('$').ok_or(NmeaError::MissingStart)?;
+    let (body, cs) = rest.rsplit_once('*').ok_or(NmeaError::MissingChecksum)?;
+    let found = match cs.as_bytes() {
+        [hi, lo] => parse_hex(*hi)
+            .zip(parse_hex(*lo))
+            .map(|(hi, lo)| hi << 4 | lo)
+            .ok_or(NmeaError::MissingChecksum)?,
+        _ => return Err(NmeaError::MissingChecksum),
+    };
+    let expected = nmea_checksum(body.as_bytes());
+    if expected != found {
+        return Err(NmeaError::BadChecksum { expected, found });
+    }
+
+    let mut fields = body.split(',');
+    let address = fields.next().unwrap_or("");
+    let mut number = |position: usize| -> Result<f32, NmeaError> {
+        fields
+            .next()
+            .and_then(|f| f.parse().ok())
+            .ok_or(NmeaError::BadField(position))
+    };
+    if address == "PRVC" {
+        Ok(NmeaSentence::Rvc(RvcSentence {
+            yaw: number(0)?,
+            pitch: number(1)?,
+            roll: number(2)?,
+            acc: [number(3)?, number(4)?, number(5)?],
+        }))
+    } else if address.len() == 5 && address.ends_with("HDT") {
+        Ok(NmeaSentence::Heading(number(0)?))
+    } else {
+        Err(NmeaError::UnknownSentence)
+    }
+}
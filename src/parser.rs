@@ -1,10 +1,17 @@
+use crate::motion::{decode_intent, decode_request, MotionIntent, MotionRequest, SensorVariant};
+#[cfg(feature = "float")]
+use crate::units::{Acceleration, Angle, G_ACCELERATION};
+use crate::validation::{InvalidReason, ValidationMode, Validator};
 use crate::Error;
 use crate::{BNO08X_UART_RVC_FRAME_SIZE, BNO08X_UART_RVC_HEADER, BUFFER_SIZE};
 use bbqueue::Consumer;
 use core::borrow::Borrow;
+#[cfg(feature = "fixed")]
+use fixed::types::I16F16;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Bno08xRvcRawFrame {
     pub index: u8,
     pub yaw: i16,
@@ -19,9 +26,9 @@ pub struct Bno08xRvcRawFrame {
     pub csum: u8,
 }
 
-const G_ACCELERATION: f32 = 9.80665;
-
+#[cfg(feature = "float")]
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bno08xRvcPrettyFrame {
     pub index: u8,  // A monotonically increasing 8-bit count is provided (0-255) per report
     pub yaw: f32, // The yaw is a measure of the rotation around the Z-axis since reset. The yaw has a range of +/- 180̊  and is provided in 0.01̊  increments.
@@ -35,7 +42,140 @@ pub struct Bno08xRvcPrettyFrame {
     pub rsvd: u8, // The message is terminated with one (BNO086) or three (otherwise) reserved bytes, currently set to zero
 }
 
+#[cfg(feature = "float")]
+impl Bno08xRvcPrettyFrame {
+    pub fn yaw_angle(&self) -> Angle {
+        Angle::from_degrees(self.yaw)
+    }
+
+    pub fn pitch_angle(&self) -> Angle {
+        Angle::from_degrees(self.pitch)
+    }
+
+    pub fn roll_angle(&self) -> Angle {
+        Angle::from_degrees(self.roll)
+    }
+
+    pub fn decode_motion_intent(&self, variant: SensorVariant) -> Option<MotionIntent> {
+        decode_intent(variant, self.motion_intent)
+    }
+
+    pub fn decode_motion_request(&self, variant: SensorVariant) -> Option<MotionRequest> {
+        decode_request(variant, self.motion_request)
+    }
+
+    pub fn x_acceleration(&self) -> Acceleration {
+        Acceleration::from_m_s2(self.x_acc)
+    }
+
+    pub fn y_acceleration(&self) -> Acceleration {
+        Acceleration::from_m_s2(self.y_acc)
+    }
+
+    pub fn z_acceleration(&self) -> Acceleration {
+        Acceleration::from_m_s2(self.z_acc)
+    }
+}
+
+#[cfg(feature = "fixed")]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bno08xRvcFixedFrame {
+    pub index: u8,
+    pub yaw: I16F16,   // degrees
+    pub pitch: I16F16, // degrees
+    pub roll: I16F16,  // degrees
+    pub x_acc: I16F16, // m/s2
+    pub y_acc: I16F16, // m/s2
+    pub z_acc: I16F16, // m/s2
+    pub motion_intent: u8,
+    pub motion_request: u8,
+    pub rsvd: u8,
+}
+
+// 1 mg = 9.80665 mm/s2 = 9 + 16133 / 20000, split so the product never leaves i32
+fn milli_g_to_mm_s2(milli_g: i16) -> i32 {
+    let milli_g = milli_g as i32;
+    let frac = milli_g * 16133;
+    let rounded = (if frac < 0 { frac - 10000 } else { frac + 10000 }) / 20000;
+    milli_g * 9 + rounded
+}
+
+#[cfg(feature = "fixed")]
+fn mm_s2_to_fixed(mm_s2: i32) -> I16F16 {
+    I16F16::from_num(mm_s2 / 1000) + I16F16::from_num(mm_s2 % 1000) / 1000
+}
+
 impl Bno08xRvcRawFrame {
+    /// Yaw in 0.01° units, as transmitted by the sensor.
+    pub fn yaw_centideg(&self) -> i16 {
+        self.yaw
+    }
+
+    /// Pitch in 0.01° units, as transmitted by the sensor.
+    pub fn pitch_centideg(&self) -> i16 {
+        self.pitch
+    }
+
+    /// Roll in 0.01° units, as transmitted by the sensor.
+    pub fn roll_centideg(&self) -> i16 {
+        self.roll
+    }
+
+    pub fn x_acc_milli_g(&self) -> i16 {
+        self.x_acc
+    }
+
+    pub fn y_acc_milli_g(&self) -> i16 {
+        self.y_acc
+    }
+
+    pub fn z_acc_milli_g(&self) -> i16 {
+        self.z_acc
+    }
+
+    /// X-axis acceleration in mm/s2, computed with integer math only.
+    pub fn x_acc_mm_s2(&self) -> i32 {
+        milli_g_to_mm_s2(self.x_acc)
+    }
+
+    /// Y-axis acceleration in mm/s2, computed with integer math only.
+    pub fn y_acc_mm_s2(&self) -> i32 {
+        milli_g_to_mm_s2(self.y_acc)
+    }
+
+    /// Z-axis acceleration in mm/s2, computed with integer math only.
+    pub fn z_acc_mm_s2(&self) -> i32 {
+        milli_g_to_mm_s2(self.z_acc)
+    }
+
+    /// Motion intent, `None` unless `variant` is a BNO086.
+    pub fn decode_motion_intent(&self, variant: SensorVariant) -> Option<MotionIntent> {
+        decode_intent(variant, self.motion_intent)
+    }
+
+    /// Motion request, `None` unless `variant` is a BNO086.
+    pub fn decode_motion_request(&self, variant: SensorVariant) -> Option<MotionRequest> {
+        decode_request(variant, self.motion_request)
+    }
+
+    #[cfg(feature = "fixed")]
+    pub fn as_fixed_frame(&self) -> Bno08xRvcFixedFrame {
+        Bno08xRvcFixedFrame {
+            index: self.index,
+            yaw: I16F16::from_num(self.yaw) / 100,
+            pitch: I16F16::from_num(self.pitch) / 100,
+            roll: I16F16::from_num(self.roll) / 100,
+            x_acc: mm_s2_to_fixed(self.x_acc_mm_s2()),
+            y_acc: mm_s2_to_fixed(self.y_acc_mm_s2()),
+            z_acc: mm_s2_to_fixed(self.z_acc_mm_s2()),
+            motion_intent: self.motion_intent,
+            motion_request: self.motion_request,
+            rsvd: self.rsvd,
+        }
+    }
+
+    #[cfg(feature = "float")]
     fn convert(&self) -> Bno08xRvcPrettyFrame {
         Bno08xRvcPrettyFrame {
             index: self.index,
@@ -51,10 +191,12 @@ impl Bno08xRvcRawFrame {
         }
     }
 
+    #[cfg(feature = "float")]
     pub fn as_pretty_frame(&self) -> Bno08xRvcPrettyFrame {
         self.convert()
     }
 
+    #[cfg(feature = "float")]
     pub fn as_pretty_closure<F: FnMut(&Bno08xRvcPrettyFrame)>(&self, mut f: F) {
         f(&self.convert());
     }
@@ -72,6 +214,9 @@ pub struct Parser {
     pub(crate) consumer: Consumer<'static, BUFFER_SIZE>,
     last_frame: Option<Bno08xRvcRawFrame>,
     state: State,
+    variant: SensorVariant,
+    validator: Option<Validator>,
+    last_invalid: Option<InvalidReason>,
 }
 
 impl Parser {
@@ -80,15 +225,47 @@ impl Parser {
             consumer,
             last_frame: None,
             state: State::LookingForFirstHeaderByte,
+            variant: SensorVariant::Bno080,
+            validator: None,
+            last_invalid: None,
         }
     }
 
+    /// Installs (or removes) a plausibility check run on every
+    /// checksum-valid frame.
+    pub fn set_validator(&mut self, validator: Option<Validator>) {
+        self.validator = validator;
+        self.last_invalid = None;
+    }
+
+    /// Reason the most recent checksum-valid frame failed validation, `None`
+    /// if it passed or no validator is installed.
+    pub fn get_last_invalid_reason(&self) -> Option<InvalidReason> {
+        self.last_invalid
+    }
+
+    pub fn set_sensor_variant(&mut self, variant: SensorVariant) {
+        self.variant = variant;
+    }
+
+    pub fn get_sensor_variant(&self) -> SensorVariant {
+        self.variant
+    }
+
+    pub fn get_last_motion_intent(&self) -> Option<MotionIntent> {
+        self.last_frame?.decode_motion_intent(self.variant)
+    }
+
+    pub fn get_last_motion_request(&self) -> Option<MotionRequest> {
+        self.last_frame?.decode_motion_request(self.variant)
+    }
+
     pub fn get_last_raw_frame(&self) -> Option<Bno08xRvcRawFrame> {
         self.last_frame
     }
 
     pub fn worker<F: FnMut(&Bno08xRvcRawFrame)>(&mut self, mut f_opt: F) -> Result<(), Error> {
-        return match self.consumer.split_read() {
+        match self.consumer.split_read() {
             Err(e) => Err(Error::BbqError(e)),
             Ok(rgr) => {
                 let (s1, s2) = rgr.bufs();
@@ -109,7 +286,32 @@ impl Parser {
                     }
                 }
             }
-        };
+        }
+    }
+
+    /// Calls `worker` until the queued bytes stop shrinking, so every
+    /// complete frame already in the buffer is handled.
+    pub fn drain<F: FnMut(&Bno08xRvcRawFrame)>(&mut self, mut f_opt: F) -> Result<(), Error> {
+        let mut pending = self.pending_len();
+        while pending > 0 {
+            self.worker(&mut f_opt)?;
+            let left = self.pending_len();
+            if left == pending {
+                break;
+            }
+            pending = left;
+        }
+        Ok(())
+    }
+
+    fn pending_len(&mut self) -> usize {
+        match self.consumer.split_read() {
+            Ok(rgr) => {
+                let (s1, s2) = rgr.bufs();
+                s1.len() + s2.len()
+            }
+            Err(_) => 0,
+        }
     }
 
     fn parse(&mut self, raw_bytes: &[u8]) -> Option<(Option<Bno08xRvcRawFrame>, usize)> {
@@ -140,11 +342,23 @@ impl Parser {
                             .map(|v| *v as u32)
                             .sum::<u32>() as u8;
                         let frame_unchecked: Bno08xRvcRawFrame = postcard::from_bytes(data).ok()?;
+                        release_size = idx + BNO08X_UART_RVC_FRAME_SIZE - 2;
+                        self.state = State::GotFrame;
                         if csum == frame_unchecked.csum {
+                            if let Some(validator) = self.validator.as_mut() {
+                                self.last_invalid = validator.check(&frame_unchecked).err();
+                                if self.last_invalid.is_some()
+                                    && validator.mode() == ValidationMode::Reject
+                                {
+                                    self.state = State::LookingForFirstHeaderByte;
+                                    return Some((None, release_size));
+                                }
+                            }
                             self.last_frame = Some(frame_unchecked);
+                        } else {
+                            self.state = State::LookingForFirstHeaderByte;
+                            return Some((None, release_size));
                         }
-                        release_size = idx + BNO08X_UART_RVC_FRAME_SIZE - 2;
-                        self.state = State::GotFrame;
                     } else {
                         self.state = State::LookingForFirstHeaderByte;
                     }
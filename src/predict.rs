@@ -0,0 +1,67 @@
+use crate::angular_velocity::{AngularVelocity, AngularVelocityEstimator};
+use crate::parser::Bno08xRvcPrettyFrame;
+use crate::quaternion::Quaternion;
+use crate::units::wrap_degrees;
+use crate::{BNO08X_UART_RVC_BAUD_RATE, BNO08X_UART_RVC_FRAME_SIZE};
+
+/// Time to shift one frame over the UART (start + 8 data + stop bits per
+/// byte), the minimum delay between sampling and the frame being parsed.
+pub const UART_FRAME_LATENCY_US: u32 =
+    (BNO08X_UART_RVC_FRAME_SIZE as u32 * 10 * 1_000_000) / BNO08X_UART_RVC_BAUD_RATE;
+
+/// Extrapolates orientation forward by a fixed latency at the estimated
+/// angular rate. Acceleration is passed through unchanged.
+pub struct Predictor {
+    latency_s: f32,
+    estimator: AngularVelocityEstimator,
+}
+
+impl Predictor {
+    pub fn new(latency_us: u32, estimator: AngularVelocityEstimator) -> Self {
+        Predictor {
+            latency_s: latency_us as f32 / 1e6,
+            estimator,
+        }
+    }
+
+    pub fn set_latency_us(&mut self, latency_us: u32) {
+        self.latency_s = latency_us as f32 / 1e6;
+    }
+
+    /// Frame as it is expected to be `latency` after `frame` was sampled.
+    /// Returned unchanged until a rate estimate is available.
+    pub fn update(&mut self, frame: &Bno08xRvcPrettyFrame) -> Bno08xRvcPrettyFrame {
+        match self.estimator.update(frame) {
+            None => *frame,
+            Some(rate) => self.extrapolate(frame, rate),
+        }
+    }
+
+    pub fn angular_velocity(&self) -> Option<AngularVelocity> {
+        self.estimator.estimate()
+    }
+
+    pub fn reset(&mut self) {
+        self.estimator.reset();
+    }
+
+    fn extrapolate(
+        &self,
+        frame: &Bno08xRvcPrettyFrame,
+        rate: AngularVelocity,
+    ) -> Bno08xRvcPrettyFrame {
+        let step = Quaternion::from_rotation_vector([
+            rate.x * self.latency_s,
+            rate.y * self.latency_s,
+            rate.z * self.latency_s,
+        ]);
+        // Body-frame rate, so the increment is applied on the right.
+        let (yaw, pitch, roll) = (frame.orientation() * step).normalized().to_euler_degrees();
+        Bno08xRvcPrettyFrame {
+            yaw: wrap_degrees(yaw),
+            pitch,
+            roll: wrap_degrees(roll),
+            ..*frame
+        }
+    }
+}
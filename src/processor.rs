@@ -16,9 +16,9 @@ impl Processor {
             Ok(mut wgr) => {
                 wgr.copy_from_slice(slice);
                 wgr.commit(slice.len());
-                return Ok(());
+                Ok(())
             }
-            Err(e) => return Err(Error::BbqError(e)),
+            Err(e) => Err(Error::BbqError(e)),
         }
     }
 }
@@ -0,0 +1,248 @@
+use crate::parser::Bno08xRvcPrettyFrame;
+use crate::units::Angle;
+use core::ops::Mul;
+
+/// Unit quaternion used to express orientations, `w + xi + yj + zk`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quaternion {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Quaternion {
+    pub const IDENTITY: Quaternion = Quaternion {
+        w: 1.0,
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+
+    /// Sensor-to-world rotation from RVC angles in degrees.
+    ///
+    /// Rotations are applied in the order yaw (Z), pitch (Y), then roll (X),
+    /// as the datasheet specifies: `q = qz(yaw) * qy(pitch) * qx(roll)`.
+    pub fn from_euler_degrees(yaw: f32, pitch: f32, roll: f32) -> Quaternion {
+        let (sy, cy) = libm::sincosf(yaw.to_radians() / 2.0);
+        let (sp, cp) = libm::sincosf(pitch.to_radians() / 2.0);
+        let (sr, cr) = libm::sincosf(roll.to_radians() / 2.0);
+        Quaternion {
+            w: cr * cp * cy + sr * sp * sy,
+            x: sr * cp * cy - cr * sp * sy,
+            y: cr * sp * cy + sr * cp * sy,
+            z: cr * cp * sy - sr * sp * cy,
+        }
+    }
+
+    /// Rotation of `degrees` around `axis`, which does not need to be normalized.
+    pub fn from_axis_angle_degrees(axis: [f32; 3], degrees: f32) -> Quaternion {
+        let n = libm::sqrtf(axis[0] * axis[0] + axis[1] * axis[1] + axis[2] * axis[2]);
+        if n == 0.0 {
+            return Quaternion::IDENTITY;
+        }
+        let (s, c) = libm::sincosf(degrees.to_radians() / 2.0);
+        Quaternion {
+            w: c,
+            x: s * axis[0] / n,
+            y: s * axis[1] / n,
+            z: s * axis[2] / n,
+        }
+    }
+
+    /// Rotation of `|v|` radians about `v`.
+    pub fn from_rotation_vector(v: [f32; 3]) -> Quaternion {
+        let angle = libm::sqrtf(v[0] * v[0] + v[1] * v[1] + v[2] * v[2]);
+        if angle < 1e-9 {
+            return Quaternion {
+                w: 1.0,
+                x: v[0] / 2.0,
+                y: v[1] / 2.0,
+                z: v[2] / 2.0,
+            }
+            .normalized();
+        }
+        Quaternion::from_axis_angle_degrees(v, angle.to_degrees())
+    }
+
+    /// Axis scaled by angle in radians, along the shortest rotation
+    /// (angle within [0, π]).
+    pub fn to_rotation_vector(&self) -> [f32; 3] {
+        let q = if self.w < 0.0 {
+            Quaternion {
+                w: -self.w,
+                x: -self.x,
+                y: -self.y,
+                z: -self.z,
+            }
+        } else {
+            *self
+        };
+        let sin_half = libm::sqrtf(q.x * q.x + q.y * q.y + q.z * q.z);
+        if sin_half < 1e-9 {
+            return [2.0 * q.x, 2.0 * q.y, 2.0 * q.z];
+        }
+        let angle = 2.0 * libm::atan2f(sin_half, q.w);
+        let k = angle / sin_half;
+        [q.x * k, q.y * k, q.z * k]
+    }
+
+    /// Row-major rotation matrix of this (unit) quaternion.
+    pub fn to_rotation_matrix(&self) -> [[f32; 3]; 3] {
+        let Quaternion { w, x, y, z } = *self;
+        [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ]
+    }
+
+    /// Inverse of `from_euler_degrees`, returns `(yaw, pitch, roll)` in degrees.
+    pub fn to_euler_degrees(&self) -> (f32, f32, f32) {
+        let Quaternion { w, x, y, z } = *self;
+        let yaw = libm::atan2f(2.0 * (w * z + x * y), 1.0 - 2.0 * (y * y + z * z));
+        let pitch = libm::asinf((2.0 * (w * y - z * x)).clamp(-1.0, 1.0));
+        let roll = libm::atan2f(2.0 * (w * x + y * z), 1.0 - 2.0 * (x * x + y * y));
+        (yaw.to_degrees(), pitch.to_degrees(), roll.to_degrees())
+    }
+
+    pub fn conjugate(&self) -> Quaternion {
+        Quaternion {
+            w: self.w,
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+
+    pub fn dot(&self, other: &Quaternion) -> f32 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn norm(&self) -> f32 {
+        libm::sqrtf(self.dot(self))
+    }
+
+    pub fn normalized(&self) -> Quaternion {
+        let n = self.norm();
+        if n == 0.0 {
+            return Quaternion::IDENTITY;
+        }
+        Quaternion {
+            w: self.w / n,
+            x: self.x / n,
+            y: self.y / n,
+            z: self.z / n,
+        }
+    }
+
+    /// Spherical linear interpolation along the shortest arc. `t` outside
+    /// `[0, 1]` extrapolates at the same angular rate.
+    pub fn slerp(&self, other: &Quaternion, t: f32) -> Quaternion {
+        let mut end = *other;
+        let mut cos_theta = self.dot(other);
+        if cos_theta < 0.0 {
+            end = Quaternion {
+                w: -end.w,
+                x: -end.x,
+                y: -end.y,
+                z: -end.z,
+            };
+            cos_theta = -cos_theta;
+        }
+        let (a, b) = if cos_theta > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta = libm::acosf(cos_theta.min(1.0));
+            let sin_theta = libm::sinf(theta);
+            (
+                libm::sinf((1.0 - t) * theta) / sin_theta,
+                libm::sinf(t * theta) / sin_theta,
+            )
+        };
+        Quaternion {
+            w: a * self.w + b * end.w,
+            x: a * self.x + b * end.x,
+            y: a * self.y + b * end.y,
+            z: a * self.z + b * end.z,
+        }
+        .normalized()
+    }
+
+    /// Rotates `v` by this quaternion (sensor frame to world frame for
+    /// orientations built by `from_euler_degrees`).
+    pub fn rotate(&self, v: [f32; 3]) -> [f32; 3] {
+        let p = Quaternion {
+            w: 0.0,
+            x: v[0],
+            y: v[1],
+            z: v[2],
+        };
+        let r = *self * p * self.conjugate();
+        [r.x, r.y, r.z]
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Quaternion;
+    fn mul(self, rhs: Quaternion) -> Quaternion {
+        Quaternion {
+            w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        }
+    }
+}
+
+/// Rotation taking one frame's orientation to another's.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RelativeRotation {
+    /// `q_from⁻¹ * q_to`, expressed in the body frame of the first frame.
+    pub quaternion: Quaternion,
+    /// Unit rotation axis in the first frame's body axes, +Z when there is
+    /// no rotation.
+    pub axis: [f32; 3],
+    /// Shortest rotation angle, within [0°, 180°].
+    pub angle: Angle,
+    /// Heading change, wrapped into (-180°, 180°].
+    pub yaw_delta: Angle,
+}
+
+impl Bno08xRvcPrettyFrame {
+    pub fn orientation(&self) -> Quaternion {
+        Quaternion::from_euler_degrees(self.yaw, self.pitch, self.roll)
+    }
+
+    /// How far the sensor rotated between `self` and `other`.
+    pub fn rotation_to(&self, other: &Bno08xRvcPrettyFrame) -> RelativeRotation {
+        let quaternion = (self.orientation().conjugate() * other.orientation()).normalized();
+        let v = quaternion.to_rotation_vector();
+        let angle = libm::sqrtf(v[0] * v[0] + v[1] * v[1] + v[2] * v[2]);
+        let axis = if angle < 1e-6 {
+            [0.0, 0.0, 1.0]
+        } else {
+            [v[0] / angle, v[1] / angle, v[2] / angle]
+        };
+        RelativeRotation {
+            quaternion,
+            axis,
+            angle: Angle::from_radians(angle),
+            yaw_delta: (other.yaw_angle() - self.yaw_angle()).wrapped(),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Compact on-device session log.
+//!
+//! A log is a sequence of COBS-framed postcard `Record`s, each terminated by
+//! a `0x00` byte. The first record is always a `Payload::Header` carrying
+//! `LOG_FORMAT_VERSION`; later records hold either a parsed raw frame or a
+//! chunk of bytes as received from the UART, tagged with a microsecond
+//! timestamp.
+
+use crate::parser::Bno08xRvcRawFrame;
+use serde::{Deserialize, Serialize};
+
+pub const LOG_FORMAT_VERSION: u8 = 1;
+// Format changes bump `LOG_FORMAT_VERSION`, not the magic.
+const LOG_MAGIC: [u8; 4] = *b"RVCL";
+
+/// Largest encoded record, including COBS overhead and the delimiter.
+pub const MAX_RECORD_SIZE: usize = 128;
+/// Raw byte chunks longer than this are split over several records.
+pub const MAX_BYTES_PER_RECORD: usize = 96;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum Payload<'a> {
+    Header { magic: [u8; 4], version: u8 },
+    Frame(Bno08xRvcRawFrame),
+    Bytes(&'a [u8]),
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct Record<'a> {
+    pub timestamp_us: u64,
+    #[serde(borrow)]
+    pub payload: Payload<'a>,
+}
+
+#[derive(Debug)]
+pub enum RecordingError<E> {
+    Encode(postcard::Error),
+    Sink(E),
+}
+
+/// Byte sink records are written to.
+///
+/// Implemented for every `embedded_io::Write`, and for `std::io::Write`
+/// through `StdSink` with the `std` feature.
+pub trait RecordSink {
+    type Error;
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+impl<T: embedded_io::Write> RecordSink for T {
+    type Error = T::Error;
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        embedded_io::Write::write_all(self, bytes)
+    }
+}
+
+#[cfg(feature = "std")]
+pub struct StdSink<W>(pub W);
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> RecordSink for StdSink<W> {
+    type Error = std::io::Error;
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.0.write_all(bytes)
+    }
+}
+
+pub struct Recorder<W: RecordSink> {
+    sink: W,
+}
+
+impl<W: RecordSink> Recorder<W> {
+    /// Starts a log on `sink` by writing the header record.
+    pub fn new(sink: W) -> Result<Self, RecordingError<W::Error>> {
+        let mut recorder = Recorder { sink };
+        recorder.write(&Record {
+            timestamp_us: 0,
+            payload: Payload::Header {
+                magic: LOG_MAGIC,
+                version: LOG_FORMAT_VERSION,
+            },
+        })?;
+        Ok(recorder)
+    }
+
+    pub fn record_frame(
+        &mut self,
+        timestamp_us: u64,
+        frame: &Bno08xRvcRawFrame,
+    ) -> Result<(), RecordingError<W::Error>> {
+        self.write(&Record {
+            timestamp_us,
+            payload: Payload::Frame(*frame),
+        })
+    }
+
+    pub fn record_bytes(
+        &mut self,
+        timestamp_us: u64,
+        bytes: &[u8],
+    ) -> Result<(), RecordingError<W::Error>> {
+        for chunk in bytes.chunks(MAX_BYTES_PER_RECORD) {
+            self.write(&Record {
+                timestamp_us,
+                payload: Payload::Bytes(chunk),
+            })?;
+        }
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.sink
+    }
+
+    fn write(&mut self, record: &Record) -> Result<(), RecordingError<W::Error>> {
+        let mut buf = [0u8; MAX_RECORD_SIZE];
+        let encoded = postcard::to_slice_cobs(record, &mut buf).map_err(RecordingError::Encode)?;
+        self.sink.write_all(encoded).map_err(RecordingError::Sink)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    MissingHeader,
+    UnsupportedVersion(u8),
+    Decode(postcard::Error),
+}
+
+/// A record together with the time elapsed since the previous one.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ReplayedRecord<'a> {
+    pub record: Record<'a>,
+    pub delay_us: u64,
+}
+
+/// Iterates over the records of a log held in memory.
+///
+/// Records are COBS-decoded in place, so the log buffer is borrowed
+/// mutably and raw byte payloads point into it. A truncated last record is
+/// ignored.
+pub struct Replayer<'a> {
+    rest: &'a mut [u8],
+    last_timestamp_us: Option<u64>,
+}
+
+impl<'a> Replayer<'a> {
+    pub fn new(log: &'a mut [u8]) -> Result<Self, ReplayError> {
+        let mut replayer = Replayer {
+            rest: log,
+            last_timestamp_us: None,
+        };
+        match replayer.next_record() {
+            Some(Ok(Record {
+                payload: Payload::Header { magic, version },
+                ..
+            })) if magic == LOG_MAGIC => {
+                if version != LOG_FORMAT_VERSION {
+                    return Err(ReplayError::UnsupportedVersion(version));
+                }
+                Ok(replayer)
+            }
+            Some(Err(e)) => Err(e),
+            _ => Err(ReplayError::MissingHeader),
+        }
+    }
+
+    fn next_record(&mut self) -> Option<Result<Record<'a>, ReplayError>> {
+        let end = self.rest.iter().position(|b| *b == 0)?;
+        let rest = core::mem::take(&mut self.rest);
+        let (chunk, tail) = rest.split_at_mut(end + 1);
+        self.rest = tail;
+        Some(postcard::from_bytes_cobs(chunk).map_err(ReplayError::Decode))
+    }
+}
+
+impl<'a> Iterator for Replayer<'a> {
+    type Item = Result<ReplayedRecord<'a>, ReplayError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_record()?.map(|record| {
+            let delay_us = match self.last_timestamp_us {
+                None => 0,
+                Some(last) => record.timestamp_us.saturating_sub(last),
+            };
+            self.last_timestamp_us = Some(record.timestamp_us);
+            ReplayedRecord { record, delay_us }
+        }))
+    }
+}
@@ -0,0 +1,159 @@
+use crate::parser::Bno08xRvcPrettyFrame;
+
+/// A frame tagged with the host time it was received at, in microseconds.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimestampedFrame {
+    pub timestamp_us: u64,
+    pub frame: Bno08xRvcPrettyFrame,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResampleError {
+    /// Fewer than two frames have been pushed.
+    NotEnoughFrames,
+    /// The query time is older than the oldest frame kept.
+    BeforeHistory,
+    /// The query time is further past the newest frame than the horizon.
+    BeyondHorizon,
+}
+
+/// Interpolates a timestamped frame stream at arbitrary query times.
+///
+/// Orientation is interpolated with quaternion slerp, acceleration
+/// linearly. Up to `N` frames are kept; queries past the newest frame are
+/// extrapolated for at most `horizon_us` (`u64::MAX` for no limit). Index and motion bytes are taken
+/// from the frame at or before the query time.
+pub struct Resampler<const N: usize> {
+    history: [Option<TimestampedFrame>; N],
+    len: usize,
+    pos: usize,
+    horizon_us: u64,
+}
+
+impl<const N: usize> Resampler<N> {
+    pub fn new(horizon_us: u64) -> Self {
+        Resampler {
+            history: [None; N],
+            len: 0,
+            pos: 0,
+            horizon_us,
+        }
+    }
+
+    /// Adds a frame. Frames not newer than the last one are ignored.
+    pub fn push(&mut self, frame: TimestampedFrame) {
+        if N == 0 {
+            return;
+        }
+        if let Some(newest) = self.get(self.len.wrapping_sub(1)) {
+            if frame.timestamp_us <= newest.timestamp_us {
+                return;
+            }
+        }
+        self.history[self.pos] = Some(frame);
+        self.pos = (self.pos + 1) % N;
+        if self.len < N {
+            self.len += 1;
+        }
+    }
+
+    pub fn newest_timestamp_us(&self) -> Option<u64> {
+        self.get(self.len.wrapping_sub(1)).map(|f| f.timestamp_us)
+    }
+
+    pub fn sample(&self, timestamp_us: u64) -> Result<TimestampedFrame, ResampleError> {
+        if self.len < 2 {
+            return Err(ResampleError::NotEnoughFrames);
+        }
+        let oldest = self.get(0).unwrap();
+        let newest = self.get(self.len - 1).unwrap();
+        if timestamp_us < oldest.timestamp_us {
+            return Err(ResampleError::BeforeHistory);
+        }
+        if timestamp_us > newest.timestamp_us.saturating_add(self.horizon_us) {
+            return Err(ResampleError::BeyondHorizon);
+        }
+
+        // Pair bracketing the query, or the newest pair when extrapolating.
+        let mut a = self.get(self.len - 2).unwrap();
+        let mut b = newest;
+        for i in 1..self.len {
+            let next = self.get(i).unwrap();
+            if next.timestamp_us >= timestamp_us {
+                a = self.get(i - 1).unwrap();
+                b = next;
+                break;
+            }
+        }
+
+        let span = (b.timestamp_us - a.timestamp_us) as f32;
+        let t = (timestamp_us as f64 - a.timestamp_us as f64) as f32 / span;
+        let q = a.frame.orientation().slerp(&b.frame.orientation(), t);
+        let (yaw, pitch, roll) = q.to_euler_degrees();
+        let lerp = |x: f32, y: f32| x + (y - x) * t;
+        let base = if t >= 1.0 { b.frame } else { a.frame };
+        Ok(TimestampedFrame {
+            timestamp_us,
+            frame: Bno08xRvcPrettyFrame {
+                yaw,
+                pitch,
+                roll,
+                x_acc: lerp(a.frame.x_acc, b.frame.x_acc),
+                y_acc: lerp(a.frame.y_acc, b.frame.y_acc),
+                z_acc: lerp(a.frame.z_acc, b.frame.z_acc),
+                ..base
+            },
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.history = [None; N];
+        self.len = 0;
+        self.pos = 0;
+    }
+
+    // `i`-th frame counting from the oldest one kept.
+    fn get(&self, i: usize) -> Option<TimestampedFrame> {
+        if i >= self.len {
+            return None;
+        }
+        self.history[(self.pos + N - self.len + i) % N]
+    }
+}
+
+/// Emits resampled frames on a fixed output period.
+pub struct FixedRateClock {
+    period_us: u64,
+    next_us: Option<u64>,
+}
+
+impl FixedRateClock {
+    /// `period_us` is clamped to at least 1.
+    pub fn new(period_us: u64) -> Self {
+        FixedRateClock {
+            period_us: period_us.max(1),
+            next_us: None,
+        }
+    }
+
+    /// `rate_hz` is clamped to 1 to 1 MHz.
+    pub fn from_rate_hz(rate_hz: u32) -> Self {
+        Self::new(1_000_000 / rate_hz.clamp(1, 1_000_000) as u64)
+    }
+
+    /// Returns the next due output, if the resampler already holds data up
+    /// to it. Call repeatedly after each `push` until it returns `None`.
+    pub fn poll<const N: usize>(
+        &mut self,
+        resampler: &Resampler<N>,
+    ) -> Option<Result<TimestampedFrame, ResampleError>> {
+        let newest = resampler.newest_timestamp_us()?;
+        let next = *self.next_us.get_or_insert(newest);
+        if next > newest {
+            return None;
+        }
+        self.next_us = Some(next.saturating_add(self.period_us));
+        Some(resampler.sample(next))
+    }
+}
@@ -0,0 +1,184 @@
+//! `sensor_msgs/Imu` compatible output.
+//!
+//! Frames are mapped to ROS REP 103 axes (`FrameConvention::Enu`). RVC mode
+//! has no gyro, so the angular velocity is zero and flagged unknown with
+//! `angular_velocity_covariance[0] = -1`, as the message definition asks.
+//! `RosImu` serialises to the ROS 1 wire format and to ROS 2 CDR
+//! (little-endian, with encapsulation header).
+
+use crate::convention::FrameConvention;
+use crate::parser::Bno08xRvcPrettyFrame;
+
+/// Row-major 3x3 covariance, as in `sensor_msgs/Imu`.
+pub type Covariance = [f64; 9];
+
+pub const UNKNOWN_COVARIANCE: Covariance = [-1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+
+pub const fn diagonal_covariance(x: f64, y: f64, z: f64) -> Covariance {
+    [x, 0.0, 0.0, 0.0, y, 0.0, 0.0, 0.0, z]
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RosImuConfig {
+    pub orientation_covariance: Covariance,         // rad2
+    pub linear_acceleration_covariance: Covariance, // (m/s2)2
+}
+
+impl Default for RosImuConfig {
+    /// Rough figures from the BNO08x datasheet: about 2° orientation and
+    /// 0.3 m/s2 acceleration accuracy (1 sigma).
+    fn default() -> Self {
+        RosImuConfig {
+            orientation_covariance: diagonal_covariance(0.0012, 0.0012, 0.0012),
+            linear_acceleration_covariance: diagonal_covariance(0.09, 0.09, 0.09),
+        }
+    }
+}
+
+/// `builtin_interfaces/Time` (ROS 2) / `time` (ROS 1).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RosTime {
+    pub sec: u32,
+    pub nanosec: u32,
+}
+
+impl RosTime {
+    pub fn from_micros(timestamp_us: u64) -> Self {
+        RosTime {
+            sec: (timestamp_us / 1_000_000) as u32,
+            nanosec: (timestamp_us % 1_000_000) as u32 * 1000,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RosError {
+    BufferTooSmall,
+}
+
+/// `sensor_msgs/Imu`, including its `std_msgs/Header`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RosImu<'a> {
+    pub stamp: RosTime,
+    pub frame_id: &'a str,
+    pub orientation: [f64; 4], // x, y, z, w
+    pub orientation_covariance: Covariance,
+    pub angular_velocity: [f64; 3], // rad/s
+    pub angular_velocity_covariance: Covariance,
+    pub linear_acceleration: [f64; 3], // m/s2
+    pub linear_acceleration_covariance: Covariance,
+}
+
+impl Bno08xRvcPrettyFrame {
+    pub fn to_ros_imu<'a>(
+        &self,
+        config: &RosImuConfig,
+        stamp: RosTime,
+        frame_id: &'a str,
+    ) -> RosImu<'a> {
+        let enu = self.to_convention(FrameConvention::Enu);
+        let q = enu.orientation;
+        RosImu {
+            stamp,
+            frame_id,
+            orientation: [q.x as f64, q.y as f64, q.z as f64, q.w as f64],
+            orientation_covariance: config.orientation_covariance,
+            angular_velocity: [0.0; 3],
+            angular_velocity_covariance: UNKNOWN_COVARIANCE,
+            linear_acceleration: [enu.acc[0] as f64, enu.acc[1] as f64, enu.acc[2] as f64],
+            linear_acceleration_covariance: config.linear_acceleration_covariance,
+        }
+    }
+}
+
+struct Writer<'b> {
+    buf: &'b mut [u8],
+    pos: usize,
+    // Start of the CDR body, alignment is relative to it.
+    origin: usize,
+    align: bool,
+}
+
+impl<'b> Writer<'b> {
+    fn put(&mut self, bytes: &[u8]) -> Result<(), RosError> {
+        let end = self.pos + bytes.len();
+        self.buf
+            .get_mut(self.pos..end)
+            .ok_or(RosError::BufferTooSmall)?
+            .copy_from_slice(bytes);
+        self.pos = end;
+        Ok(())
+    }
+
+    // CDR alignments are powers of two.
+    fn pad(&mut self, size: usize) -> Result<(), RosError> {
+        if self.align {
+            while (self.pos - self.origin) & (size - 1) != 0 {
+                self.put(&[0])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn u32(&mut self, v: u32) -> Result<(), RosError> {
+        self.pad(4)?;
+        self.put(&v.to_le_bytes())
+    }
+
+    fn f64s(&mut self, values: &[f64]) -> Result<(), RosError> {
+        for v in values {
+            self.pad(8)?;
+            self.put(&v.to_le_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> RosImu<'a> {
+    /// ROS 1 serialisation, without the TCPROS length prefix.
+    pub fn to_ros1(&self, seq: u32, buf: &mut [u8]) -> Result<usize, RosError> {
+        let mut w = Writer {
+            buf,
+            pos: 0,
+            origin: 0,
+            align: false,
+        };
+        w.u32(seq)?;
+        w.u32(self.stamp.sec)?;
+        w.u32(self.stamp.nanosec)?;
+        w.u32(self.frame_id.len() as u32)?;
+        w.put(self.frame_id.as_bytes())?;
+        self.put_body(&mut w)?;
+        Ok(w.pos)
+    }
+
+    /// ROS 2 CDR serialisation, starting with the little-endian
+    /// encapsulation header.
+    pub fn to_cdr(&self, buf: &mut [u8]) -> Result<usize, RosError> {
+        let mut w = Writer {
+            buf,
+            pos: 0,
+            origin: 4,
+            align: true,
+        };
+        w.put(&[0x00, 0x01, 0x00, 0x00])?;
+        w.u32(self.stamp.sec)?;
+        w.u32(self.stamp.nanosec)?;
+        w.u32(self.frame_id.len() as u32 + 1)?;
+        w.put(self.frame_id.as_bytes())?;
+        w.put(&[0])?;
+        self.put_body(&mut w)?;
+        Ok(w.pos)
+    }
+
+    fn put_body(&self, w: &mut Writer) -> Result<(), RosError> {
+        w.f64s(&self.orientation)?;
+        w.f64s(&self.orientation_covariance)?;
+        w.f64s(&self.angular_velocity)?;
+        w.f64s(&self.angular_velocity_covariance)?;
+        w.f64s(&self.linear_acceleration)?;
+        w.f64s(&self.linear_acceleration_covariance)
+    }
+}
@@ -0,0 +1,238 @@
+use crate::parser::Bno08xRvcPrettyFrame;
+
+const AXES: usize = 3;
+
+// Smallest window with a meaningful median to compare a sample against.
+const MIN_WINDOW: usize = 3;
+
+// Scale factor turning the median absolute deviation into a standard
+// deviation estimate for normally distributed noise.
+const MAD_TO_SIGMA: f32 = 1.4826;
+
+/// Acceleration channels replaced by a spike filter for one frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReplacedChannels {
+    pub x_acc: bool,
+    pub y_acc: bool,
+    pub z_acc: bool,
+}
+
+impl ReplacedChannels {
+    pub fn any(&self) -> bool {
+        self.x_acc || self.y_acc || self.z_acc
+    }
+
+    pub fn count(&self) -> u32 {
+        self.x_acc as u32 + self.y_acc as u32 + self.z_acc as u32
+    }
+
+    fn set(&mut self, axis: usize) {
+        match axis {
+            0 => self.x_acc = true,
+            1 => self.y_acc = true,
+            _ => self.z_acc = true,
+        }
+    }
+}
+
+/// Running spike counters, kept by each filter until `reset`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpikeStats {
+    pub frames: u32,
+    pub spiky_frames: u32,
+    pub x_acc: u32,
+    pub y_acc: u32,
+    pub z_acc: u32,
+}
+
+impl SpikeStats {
+    fn record(&mut self, replaced: ReplacedChannels) {
+        self.frames = self.frames.wrapping_add(1);
+        self.spiky_frames = self.spiky_frames.wrapping_add(replaced.any() as u32);
+        self.x_acc = self.x_acc.wrapping_add(replaced.x_acc as u32);
+        self.y_acc = self.y_acc.wrapping_add(replaced.y_acc as u32);
+        self.z_acc = self.z_acc.wrapping_add(replaced.z_acc as u32);
+    }
+}
+
+struct AccWindow<const N: usize> {
+    samples: [[f32; AXES]; N],
+    len: usize,
+    pos: usize,
+}
+
+impl<const N: usize> AccWindow<N> {
+    // Evaluated when a filter with window `N` is built, so a window too
+    // short to ever replace anything fails to compile.
+    const WINDOW_CHECK: () = assert!(N >= MIN_WINDOW, "spike filter window must be at least 3");
+
+    fn new() -> Self {
+        let () = Self::WINDOW_CHECK;
+        AccWindow {
+            samples: [[0.0; AXES]; N],
+            len: 0,
+            pos: 0,
+        }
+    }
+
+    fn push(&mut self, acc: [f32; AXES]) {
+        self.samples[self.pos] = acc;
+        self.pos = (self.pos + 1) % N;
+        if self.len < N {
+            self.len += 1;
+        }
+    }
+
+    fn median(&self, axis: usize) -> f32 {
+        let mut values = [0.0f32; N];
+        for (value, sample) in values.iter_mut().zip(&self.samples[..self.len]) {
+            *value = sample[axis];
+        }
+        median_of(&mut values[..self.len])
+    }
+
+    fn mad(&self, axis: usize, median: f32) -> f32 {
+        let mut values = [0.0f32; N];
+        for (value, sample) in values.iter_mut().zip(&self.samples[..self.len]) {
+            *value = (sample[axis] - median).abs();
+        }
+        median_of(&mut values[..self.len])
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+        self.pos = 0;
+    }
+}
+
+fn median_of(values: &mut [f32]) -> f32 {
+    values.sort_unstable_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len() & 1 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+fn acc(frame: &Bno08xRvcPrettyFrame) -> [f32; AXES] {
+    [frame.x_acc, frame.y_acc, frame.z_acc]
+}
+
+fn with_acc(frame: &Bno08xRvcPrettyFrame, acc: [f32; AXES]) -> Bno08xRvcPrettyFrame {
+    Bno08xRvcPrettyFrame {
+        x_acc: acc[0],
+        y_acc: acc[1],
+        z_acc: acc[2],
+        ..*frame
+    }
+}
+
+/// Median-of-`N` spike rejection on the acceleration channels. `N` must be
+/// at least 3, smaller windows are rejected at compile time.
+///
+/// A sample is replaced by the window median when it deviates from it by
+/// more than `threshold` m/s2. A threshold of 0 turns this into a plain
+/// running median.
+pub struct MedianFilter<const N: usize> {
+    window: AccWindow<N>,
+    threshold: f32,
+    stats: SpikeStats,
+}
+
+impl<const N: usize> MedianFilter<N> {
+    pub fn new(threshold: f32) -> Self {
+        MedianFilter {
+            window: AccWindow::new(),
+            threshold,
+            stats: SpikeStats::default(),
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        frame: &Bno08xRvcPrettyFrame,
+    ) -> (Bno08xRvcPrettyFrame, ReplacedChannels) {
+        let mut out = acc(frame);
+        self.window.push(out);
+        let mut replaced = ReplacedChannels::default();
+        if self.window.len >= MIN_WINDOW {
+            for (axis, value) in out.iter_mut().enumerate() {
+                let median = self.window.median(axis);
+                if (*value - median).abs() > self.threshold {
+                    *value = median;
+                    replaced.set(axis);
+                }
+            }
+        }
+        self.stats.record(replaced);
+        (with_acc(frame, out), replaced)
+    }
+
+    pub fn stats(&self) -> SpikeStats {
+        self.stats
+    }
+
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.stats = SpikeStats::default();
+    }
+}
+
+/// Hampel identifier over the last `N` frames on the acceleration channels.
+/// `N` must be at least 3, smaller windows are rejected at compile time.
+///
+/// A sample further than `k` scaled median absolute deviations from the
+/// window median is an outlier and is replaced by the median.
+/// `min_deviation` (m/s2) keeps a perfectly still window, whose MAD is zero,
+/// from flagging ordinary sensor noise.
+pub struct HampelFilter<const N: usize> {
+    window: AccWindow<N>,
+    k: f32,
+    min_deviation: f32,
+    stats: SpikeStats,
+}
+
+impl<const N: usize> HampelFilter<N> {
+    pub fn new(k: f32, min_deviation: f32) -> Self {
+        HampelFilter {
+            window: AccWindow::new(),
+            k,
+            min_deviation,
+            stats: SpikeStats::default(),
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        frame: &Bno08xRvcPrettyFrame,
+    ) -> (Bno08xRvcPrettyFrame, ReplacedChannels) {
+        let mut out = acc(frame);
+        self.window.push(out);
+        let mut replaced = ReplacedChannels::default();
+        if self.window.len >= MIN_WINDOW {
+            for (axis, value) in out.iter_mut().enumerate() {
+                let median = self.window.median(axis);
+                let sigma = MAD_TO_SIGMA * self.window.mad(axis, median);
+                let limit = (self.k * sigma).max(self.min_deviation);
+                if (*value - median).abs() > limit {
+                    *value = median;
+                    replaced.set(axis);
+                }
+            }
+        }
+        self.stats.record(replaced);
+        (with_acc(frame, out), replaced)
+    }
+
+    pub fn stats(&self) -> SpikeStats {
+        self.stats
+    }
+
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.stats = SpikeStats::default();
+    }
+}
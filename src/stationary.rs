@@ -0,0 +1,138 @@
+use crate::motion::{MotionIntent, SensorVariant};
+use crate::parser::Bno08xRvcPrettyFrame;
+use crate::units::{wrap_degrees, G_ACCELERATION};
+use crate::BNO08X_UART_RVC_FRAME_RATE_HZ;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StationaryConfig {
+    /// Total accel variance (m/s2)^2 below which the sensor is still.
+    pub still_variance: f32,
+    /// Total accel variance (m/s2)^2 below which a sensor that is not
+    /// rotating is stationary with vibration rather than moving.
+    pub vibration_variance: f32,
+    /// Largest yaw/pitch/roll rate of change (°/s) still counted as stationary.
+    pub max_angle_rate: f32,
+    /// Mean `| |a| - g |` (m/s2) above which motion is reported as accelerating.
+    pub accelerating_threshold: f32,
+}
+
+impl Default for StationaryConfig {
+    fn default() -> Self {
+        StationaryConfig {
+            still_variance: 0.002,
+            vibration_variance: 0.05,
+            max_angle_rate: 1.0,
+            accelerating_threshold: 0.5,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Sample {
+    index: u8,
+    angles: [f32; 3],
+    acc: [f32; 3],
+}
+
+/// Software replacement for the BNO086 motion intent on BNO080/BNO085.
+///
+/// Classifies the last `N` frames from accel variance and angle rate of
+/// change and returns the same `MotionIntent` the BNO086 reports, so
+/// downstream code does not care which part is fitted.
+pub struct StationaryDetector<const N: usize> {
+    config: StationaryConfig,
+    samples: [Sample; N],
+    len: usize,
+    pos: usize,
+}
+
+impl<const N: usize> StationaryDetector<N> {
+    pub fn new(config: StationaryConfig) -> Self {
+        StationaryDetector {
+            config,
+            samples: [Sample {
+                index: 0,
+                angles: [0.0; 3],
+                acc: [0.0; 3],
+            }; N],
+            len: 0,
+            pos: 0,
+        }
+    }
+
+    /// Software classification, `Indeterminate` until the window is full.
+    pub fn update(&mut self, frame: &Bno08xRvcPrettyFrame) -> MotionIntent {
+        if N < 2 {
+            return MotionIntent::Indeterminate;
+        }
+        self.samples[self.pos] = Sample {
+            index: frame.index,
+            angles: [frame.yaw, frame.pitch, frame.roll],
+            acc: [frame.x_acc, frame.y_acc, frame.z_acc],
+        };
+        self.pos = (self.pos + 1) % N;
+        if self.len < N {
+            self.len += 1;
+            if self.len < N {
+                return MotionIntent::Indeterminate;
+            }
+        }
+        self.classify()
+    }
+
+    /// Uses the sensor's own motion intent when `variant` provides one and
+    /// falls back to `update` otherwise. The window is fed either way.
+    pub fn update_for(
+        &mut self,
+        variant: SensorVariant,
+        frame: &Bno08xRvcPrettyFrame,
+    ) -> MotionIntent {
+        let software = self.update(frame);
+        frame.decode_motion_intent(variant).unwrap_or(software)
+    }
+
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.pos = 0;
+    }
+
+    fn classify(&self) -> MotionIntent {
+        let oldest = &self.samples[self.pos];
+        let newest = &self.samples[(self.pos + N - 1) % N];
+        let frames = newest.index.wrapping_sub(oldest.index).max(1);
+        let window_s = frames as f32 / BNO08X_UART_RVC_FRAME_RATE_HZ as f32;
+
+        let mut angle_rate = 0.0f32;
+        for axis in 0..3 {
+            let change = wrap_degrees(newest.angles[axis] - oldest.angles[axis]);
+            angle_rate = angle_rate.max(change.abs() / window_s);
+        }
+
+        let mut variance = 0.0;
+        let mut dynamic = 0.0;
+        for axis in 0..3 {
+            let mean = self.samples.iter().map(|s| s.acc[axis]).sum::<f32>() / N as f32;
+            variance += self
+                .samples
+                .iter()
+                .map(|s| (s.acc[axis] - mean) * (s.acc[axis] - mean))
+                .sum::<f32>()
+                / N as f32;
+        }
+        for s in &self.samples {
+            let magnitude = libm::sqrtf(s.acc.iter().map(|a| a * a).sum::<f32>());
+            dynamic += (magnitude - G_ACCELERATION).abs() / N as f32;
+        }
+
+        let rotating = angle_rate > self.config.max_angle_rate;
+        if !rotating && variance < self.config.still_variance {
+            MotionIntent::StationaryWithoutVibration
+        } else if !rotating && variance < self.config.vibration_variance {
+            MotionIntent::StationaryWithVibration
+        } else if dynamic > self.config.accelerating_threshold {
+            MotionIntent::InMotionAccelerating
+        } else {
+            MotionIntent::InMotion
+        }
+    }
+}
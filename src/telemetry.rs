@@ -0,0 +1,302 @@
+//! Delta-compressed raw frames for low-bandwidth links.
+//!
+//! Packet layout: `flags`, `sequence`, `mask`, then LEB128 varints. The
+//! first varint is the frame index difference, followed by the zig-zag
+//! encoded differences of the fields whose bit is set in `mask` (bit 0 yaw,
+//! then pitch, roll, x/y/z acceleration, motion intent, motion request).
+//! Keyframes (`flags` bit 0) are coded against an all-zero frame, so they
+//! decode on their own. After a lost packet the decoder drops deltas until
+//! the next keyframe. Duplicated or reordered packets, whose sequence is not
+//! ahead of the last one, are ignored.
+
+use core::convert::TryFrom;
+
+use crate::parser::Bno08xRvcRawFrame;
+
+pub const TELEMETRY_MAX_PACKET_LEN: usize = 3 + 2 + 6 * 3 + 2 * 2;
+
+const FLAG_KEYFRAME: u8 = 0x01;
+
+// Sequence differences of this size or more are taken as going backwards.
+const STALE_SEQUENCE_GAP: u8 = 128;
+
+// After this many consecutive stale packets the sender is assumed to have
+// restarted or skipped ahead by more than half the sequence range, and the
+// next packet is accepted as the new sequence.
+const MAX_CONSECUTIVE_STALE: u8 = 3;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TelemetryError {
+    BufferTooSmall,
+    Truncated,
+    Malformed,
+    /// A delta packet arrived without a usable reference, either before
+    /// the first keyframe or after a lost packet.
+    MissingKeyframe,
+    /// A duplicated or reordered packet not newer than the last one. It is
+    /// ignored and leaves the decoder state unchanged.
+    StalePacket,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TelemetryConfig {
+    /// Send one frame out of `decimation`, 1 sends every frame.
+    pub decimation: u8,
+    /// Every `keyframe_interval`-th packet is a keyframe.
+    pub keyframe_interval: u8,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        TelemetryConfig {
+            decimation: 1,
+            keyframe_interval: 50,
+        }
+    }
+}
+
+fn zigzag(v: i16) -> u16 {
+    ((v << 1) ^ (v >> 15)) as u16
+}
+
+fn unzigzag(v: u16) -> i16 {
+    ((v >> 1) as i16) ^ -((v & 1) as i16)
+}
+
+const ZERO_FRAME: Bno08xRvcRawFrame = Bno08xRvcRawFrame {
+    index: 0,
+    yaw: 0,
+    pitch: 0,
+    roll: 0,
+    x_acc: 0,
+    y_acc: 0,
+    z_acc: 0,
+    motion_intent: 0,
+    motion_request: 0,
+    rsvd: 0,
+    csum: 0,
+};
+
+fn fields(frame: &Bno08xRvcRawFrame) -> [i16; 8] {
+    [
+        frame.yaw,
+        frame.pitch,
+        frame.roll,
+        frame.x_acc,
+        frame.y_acc,
+        frame.z_acc,
+        frame.motion_intent as i16,
+        frame.motion_request as i16,
+    ]
+}
+
+struct Cursor<'b> {
+    buf: &'b [u8],
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn byte(&mut self) -> Result<u8, TelemetryError> {
+        let b = *self.buf.get(self.pos).ok_or(TelemetryError::Truncated)?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn varint(&mut self) -> Result<u16, TelemetryError> {
+        let mut value = 0u32;
+        for shift in [0, 7, 14] {
+            let b = self.byte()?;
+            value |= ((b & 0x7F) as u32) << shift;
+            if b & 0x80 == 0 {
+                return u16::try_from(value).map_err(|_| TelemetryError::Malformed);
+            }
+        }
+        Err(TelemetryError::Malformed)
+    }
+}
+
+fn put_varint(buf: &mut [u8], pos: &mut usize, mut value: u16) -> Result<(), TelemetryError> {
+    loop {
+        let slot = buf.get_mut(*pos).ok_or(TelemetryError::BufferTooSmall)?;
+        *pos += 1;
+        if value < 0x80 {
+            *slot = value as u8;
+            return Ok(());
+        }
+        *slot = (value as u8 & 0x7F) | 0x80;
+        value >>= 7;
+    }
+}
+
+pub struct TelemetryEncoder {
+    config: TelemetryConfig,
+    reference: Option<Bno08xRvcRawFrame>,
+    sequence: u8,
+    skipped: u8,
+    since_keyframe: u8,
+}
+
+impl TelemetryEncoder {
+    /// `decimation` and `keyframe_interval` of 0 are treated as 1.
+    pub fn new(config: TelemetryConfig) -> Self {
+        TelemetryEncoder {
+            config: TelemetryConfig {
+                decimation: config.decimation.max(1),
+                keyframe_interval: config.keyframe_interval.max(1),
+            },
+            reference: None,
+            sequence: 0,
+            skipped: 0,
+            since_keyframe: 0,
+        }
+    }
+
+    /// Makes the next sent packet a keyframe.
+    pub fn force_keyframe(&mut self) {
+        self.reference = None;
+    }
+
+    /// Encodes `frame` into `buf`. Returns `Ok(None)` for frames dropped by
+    /// decimation, otherwise the packet length.
+    pub fn encode(
+        &mut self,
+        frame: &Bno08xRvcRawFrame,
+        buf: &mut [u8],
+    ) -> Result<Option<usize>, TelemetryError> {
+        if self.reference.is_some() && self.skipped + 1 < self.config.decimation {
+            self.skipped += 1;
+            return Ok(None);
+        }
+        let keyframe =
+            self.reference.is_none() || self.since_keyframe >= self.config.keyframe_interval;
+        let reference = match self.reference {
+            Some(reference) if !keyframe => reference,
+            _ => ZERO_FRAME,
+        };
+
+        if buf.len() < 3 {
+            return Err(TelemetryError::BufferTooSmall);
+        }
+        let mut pos = 3;
+        put_varint(
+            buf,
+            &mut pos,
+            frame.index.wrapping_sub(reference.index) as u16,
+        )?;
+        let mut mask = 0u8;
+        for (bit, (new, old)) in fields(frame)
+            .iter()
+            .zip(fields(&reference).iter())
+            .enumerate()
+        {
+            let delta = new.wrapping_sub(*old);
+            if delta != 0 {
+                mask |= 1 << bit;
+                put_varint(buf, &mut pos, zigzag(delta))?;
+            }
+        }
+        buf[0] = if keyframe { FLAG_KEYFRAME } else { 0 };
+        buf[1] = self.sequence;
+        buf[2] = mask;
+
+        self.sequence = self.sequence.wrapping_add(1);
+        self.skipped = 0;
+        self.since_keyframe = if keyframe { 1 } else { self.since_keyframe + 1 };
+        self.reference = Some(*frame);
+        Ok(Some(pos))
+    }
+}
+
+#[derive(Default)]
+pub struct TelemetryDecoder {
+    reference: Option<Bno08xRvcRawFrame>,
+    last_sequence: Option<u8>,
+    stale: u8,
+    lost_packets: u32,
+}
+
+impl TelemetryDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes one packet. Decoded frames have `rsvd` and `csum` set to 0.
+    /// Any error other than `StalePacket` drops the reference frame, so
+    /// deltas are only applied again after the next keyframe.
+    pub fn decode(&mut self, packet: &[u8]) -> Result<Bno08xRvcRawFrame, TelemetryError> {
+        let result = self.decode_packet(packet);
+        if matches!(result, Err(e) if e != TelemetryError::StalePacket) {
+            self.reference = None;
+        }
+        result
+    }
+
+    fn decode_packet(&mut self, packet: &[u8]) -> Result<Bno08xRvcRawFrame, TelemetryError> {
+        let mut cursor = Cursor {
+            buf: packet,
+            pos: 0,
+        };
+        let flags = cursor.byte()?;
+        let sequence = cursor.byte()?;
+        let mask = cursor.byte()?;
+        let keyframe = flags & FLAG_KEYFRAME != 0;
+
+        let gap = self.last_sequence.map(|last| sequence.wrapping_sub(last));
+        match gap {
+            Some(gap) if gap == 0 || gap >= STALE_SEQUENCE_GAP => {
+                if self.stale < MAX_CONSECUTIVE_STALE {
+                    self.stale += 1;
+                    return Err(TelemetryError::StalePacket);
+                }
+                self.reference = None;
+            }
+            Some(gap) => self.lost_packets += gap as u32 - 1,
+            None => {}
+        }
+        self.stale = 0;
+        if !keyframe && gap != Some(1) {
+            self.reference = None;
+        }
+        self.last_sequence = Some(sequence);
+        let reference = match (keyframe, self.reference) {
+            (true, _) => ZERO_FRAME,
+            (false, Some(reference)) => reference,
+            (false, None) => return Err(TelemetryError::MissingKeyframe),
+        };
+
+        let index = reference
+            .index
+            .wrapping_add(u8::try_from(cursor.varint()?).map_err(|_| TelemetryError::Malformed)?);
+        let mut values = fields(&reference);
+        for (bit, value) in values.iter_mut().enumerate() {
+            if mask & (1 << bit) != 0 {
+                *value = value.wrapping_add(unzigzag(cursor.varint()?));
+            }
+        }
+        if cursor.pos != packet.len() {
+            return Err(TelemetryError::Malformed);
+        }
+        let frame = Bno08xRvcRawFrame {
+            index,
+            yaw: values[0],
+            pitch: values[1],
+            roll: values[2],
+            x_acc: values[3],
+            y_acc: values[4],
+            z_acc: values[5],
+            motion_intent: values[6] as u8,
+            motion_request: values[7] as u8,
+            rsvd: 0,
+            csum: 0,
+        };
+        self.reference = Some(frame);
+        Ok(frame)
+    }
+
+    /// Packets missing from the sequence numbers seen so far, whether or not
+    /// the surrounding packets could be decoded.
+    pub fn lost_packets(&self) -> u32 {
+        self.lost_packets
+    }
+}
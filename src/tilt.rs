@@ -0,0 +1,146 @@
+use crate::parser::Bno08xRvcPrettyFrame;
+use crate::units::Angle;
+use crate::BNO08X_UART_RVC_FRAME_RATE_HZ;
+
+/// Inclination from vertical and the direction of that inclination.
+///
+/// `azimuth` is the direction the sensor's Z axis leans towards, measured
+/// in the yaw-free horizontal plane from +X towards +Y, in (-180°, 180°].
+/// It is 0 when the sensor is level.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tilt {
+    pub inclination: Angle,
+    pub azimuth: Angle,
+}
+
+fn tilt_of(lean_x: f32, lean_y: f32, up: f32) -> Tilt {
+    let horizontal = libm::sqrtf(lean_x * lean_x + lean_y * lean_y);
+    let azimuth = if horizontal == 0.0 {
+        0.0
+    } else {
+        libm::atan2f(lean_y, lean_x)
+    };
+    Tilt {
+        inclination: Angle::from_radians(libm::atan2f(horizontal, up)),
+        azimuth: Angle::from_radians(azimuth).wrapped(),
+    }
+}
+
+impl Bno08xRvcPrettyFrame {
+    /// Tilt from the reported pitch and roll.
+    pub fn tilt(&self) -> Tilt {
+        let (sp, cp) = libm::sincosf(self.pitch_angle().radians());
+        let (sr, cr) = libm::sincosf(self.roll_angle().radians());
+        tilt_of(cr * sp, -sr, cp * cr)
+    }
+
+    /// Tilt from the accelerometer, valid while the sensor is not
+    /// accelerating. `None` in free fall.
+    pub fn tilt_from_acc(&self) -> Option<Tilt> {
+        let norm = libm::sqrtf(
+            self.x_acc * self.x_acc + self.y_acc * self.y_acc + self.z_acc * self.z_acc,
+        );
+        if norm < 1e-3 {
+            return None;
+        }
+        // Level the lean with the roll and pitch implied by gravity so the
+        // azimuth matches `tilt()`: sin(pitch) = -x / |a|, and roll comes
+        // from the y/z components. Pitched fully on its side, roll is
+        // undefined and taken as zero.
+        let yz = libm::sqrtf(self.y_acc * self.y_acc + self.z_acc * self.z_acc);
+        if yz == 0.0 {
+            return Some(tilt_of(-self.x_acc, 0.0, 0.0));
+        }
+        Some(tilt_of(
+            -self.x_acc * self.z_acc / yz,
+            -self.y_acc * norm / yz,
+            self.z_acc,
+        ))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TiltAlarmEvent {
+    Raised,
+    Cleared,
+}
+
+/// Inclination alarm with hysteresis and a hold time.
+///
+/// The alarm is raised once the inclination stays above `threshold` for
+/// `hold_ms`, and cleared once it stays below `threshold - hysteresis` for
+/// `hold_ms`, both timed from the first sample past the limit.
+pub struct TiltAlarm {
+    threshold: Angle,
+    hysteresis: Angle,
+    hold_ms: u32,
+    active: bool,
+    // Time since the first sample past the limit, `None` while within it.
+    pending_ms: Option<u32>,
+    last_index: Option<u8>,
+}
+
+impl TiltAlarm {
+    pub fn new(threshold: Angle, hysteresis: Angle, hold_ms: u32) -> Self {
+        TiltAlarm {
+            threshold,
+            hysteresis,
+            hold_ms,
+            active: false,
+            pending_ms: None,
+            last_index: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Feeds a frame, timing the hold from the frame index.
+    pub fn update(&mut self, frame: &Bno08xRvcPrettyFrame) -> Option<TiltAlarmEvent> {
+        let dt_ms = match self.last_index {
+            None => 0,
+            Some(last) => {
+                frame.index.wrapping_sub(last) as u32 * 1000 / BNO08X_UART_RVC_FRAME_RATE_HZ
+            }
+        };
+        self.last_index = Some(frame.index);
+        self.update_with_dt(frame.tilt().inclination, dt_ms)
+    }
+
+    /// Feeds an inclination observed `dt_ms` after the previous one.
+    pub fn update_with_dt(&mut self, inclination: Angle, dt_ms: u32) -> Option<TiltAlarmEvent> {
+        let crossing = if self.active {
+            inclination < self.threshold - self.hysteresis
+        } else {
+            inclination > self.threshold
+        };
+        if !crossing {
+            self.pending_ms = None;
+            return None;
+        }
+        let pending_ms = match self.pending_ms {
+            None => 0,
+            Some(pending_ms) => pending_ms.saturating_add(dt_ms),
+        };
+        if pending_ms < self.hold_ms {
+            self.pending_ms = Some(pending_ms);
+            return None;
+        }
+        self.pending_ms = None;
+        self.active = !self.active;
+        if self.active {
+            Some(TiltAlarmEvent::Raised)
+        } else {
+            Some(TiltAlarmEvent::Cleared)
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.active = false;
+        self.pending_ms = None;
+        self.last_index = None;
+    }
+}
@@ -0,0 +1,128 @@
+use core::f32::consts::PI;
+use core::ops::{Add, Mul, Neg, Sub};
+
+pub const G_ACCELERATION: f32 = 9.80665;
+
+/// Plane angle. Stored in degrees, the unit the sensor reports.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Angle(f32);
+
+impl Angle {
+    pub const fn from_degrees(degrees: f32) -> Angle {
+        Angle(degrees)
+    }
+
+    pub fn from_radians(radians: f32) -> Angle {
+        Angle(radians * 180.0 / PI)
+    }
+
+    pub fn degrees(self) -> f32 {
+        self.0
+    }
+
+    pub fn radians(self) -> f32 {
+        self.0 * PI / 180.0
+    }
+
+    /// Same direction, mapped into (-180°, 180°].
+    pub fn wrapped(self) -> Angle {
+        Angle(wrap_degrees(self.0))
+    }
+}
+
+pub(crate) fn wrap_degrees(degrees: f32) -> f32 {
+    let wrapped = degrees - 360.0 * libm::floorf((degrees + 180.0) / 360.0);
+    if wrapped <= -180.0 {
+        wrapped + 360.0
+    } else {
+        wrapped
+    }
+}
+
+impl Add for Angle {
+    type Output = Angle;
+    fn add(self, rhs: Angle) -> Angle {
+        Angle(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Angle {
+    type Output = Angle;
+    fn sub(self, rhs: Angle) -> Angle {
+        Angle(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Angle {
+    type Output = Angle;
+    fn neg(self) -> Angle {
+        Angle(-self.0)
+    }
+}
+
+impl Mul<f32> for Angle {
+    type Output = Angle;
+    fn mul(self, rhs: f32) -> Angle {
+        Angle(self.0 * rhs)
+    }
+}
+
+/// Linear acceleration. Stored in m/s2.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Acceleration(f32);
+
+impl Acceleration {
+    pub const fn from_m_s2(m_s2: f32) -> Acceleration {
+        Acceleration(m_s2)
+    }
+
+    pub fn from_g(g: f32) -> Acceleration {
+        Acceleration(g * G_ACCELERATION)
+    }
+
+    pub fn from_milli_g(milli_g: f32) -> Acceleration {
+        Acceleration(milli_g * G_ACCELERATION / 1000.0)
+    }
+
+    pub fn m_s2(self) -> f32 {
+        self.0
+    }
+
+    pub fn g(self) -> f32 {
+        self.0 / G_ACCELERATION
+    }
+
+    pub fn milli_g(self) -> f32 {
+        self.0 * 1000.0 / G_ACCELERATION
+    }
+}
+
+impl Add for Acceleration {
+    type Output = Acceleration;
+    fn add(self, rhs: Acceleration) -> Acceleration {
+        Acceleration(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Acceleration {
+    type Output = Acceleration;
+    fn sub(self, rhs: Acceleration) -> Acceleration {
+        Acceleration(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Acceleration {
+    type Output = Acceleration;
+    fn neg(self) -> Acceleration {
+        Acceleration(-self.0)
+    }
+}
+
+impl Mul<f32> for Acceleration {
+    type Output = Acceleration;
+    fn mul(self, rhs: f32) -> Acceleration {
+        Acceleration(self.0 * rhs)
+    }
+}
@@ -0,0 +1,117 @@
+use crate::parser::Bno08xRvcRawFrame;
+
+// After this many consecutive jump rejections the new orientation is taken
+// as the reference, so a genuine fast turn or a sensor reset cannot lock
+// the validator out for good.
+const MAX_CONSECUTIVE_JUMPS: u8 = 3;
+
+/// Why a checksum-valid frame was judged physically implausible.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InvalidReason {
+    YawOutOfRange,
+    PitchOutOfRange,
+    RollOutOfRange,
+    AccelerationOutOfRange,
+    OrientationJump,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Invalid frames are dropped.
+    Reject,
+    /// Invalid frames are delivered, the reason is only reported.
+    Flag,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ValidationLimits {
+    /// Largest plausible absolute acceleration per axis, mg.
+    pub max_abs_acc_milli_g: i16,
+    /// Largest plausible rotation rate of yaw, pitch or roll, °/s.
+    pub max_angle_rate_deg_s: u16,
+}
+
+impl Default for ValidationLimits {
+    fn default() -> Self {
+        ValidationLimits {
+            max_abs_acc_milli_g: 16_000,
+            max_angle_rate_deg_s: 2_000,
+        }
+    }
+}
+
+/// Range and continuity checks for raw frames, integer math only.
+pub struct Validator {
+    limits: ValidationLimits,
+    mode: ValidationMode,
+    previous: Option<Bno08xRvcRawFrame>,
+    jumps: u8,
+}
+
+// Difference of two angles in 0.01°, wrapped into [-18000, 18000).
+fn angle_delta(a: i16, b: i16) -> i32 {
+    (a as i32 - b as i32 + 18_000).rem_euclid(36_000) - 18_000
+}
+
+impl Validator {
+    pub fn new(limits: ValidationLimits, mode: ValidationMode) -> Self {
+        Validator {
+            limits,
+            mode,
+            previous: None,
+            jumps: 0,
+        }
+    }
+
+    pub fn mode(&self) -> ValidationMode {
+        self.mode
+    }
+
+    pub fn check(&mut self, frame: &Bno08xRvcRawFrame) -> Result<(), InvalidReason> {
+        Self::check_ranges(&self.limits, frame)?;
+        if let Some(previous) = self.previous {
+            // At 100 Hz, °/s times elapsed frames is the allowed change in 0.01°.
+            let frames = frame.index.wrapping_sub(previous.index).max(1) as i32;
+            let allowed = self.limits.max_angle_rate_deg_s as i32 * frames;
+            let jump = angle_delta(frame.yaw, previous.yaw).abs() > allowed
+                || (frame.pitch as i32 - previous.pitch as i32).abs() > allowed
+                || angle_delta(frame.roll, previous.roll).abs() > allowed;
+            if jump && self.jumps < MAX_CONSECUTIVE_JUMPS {
+                self.jumps += 1;
+                return Err(InvalidReason::OrientationJump);
+            }
+        }
+        self.jumps = 0;
+        self.previous = Some(*frame);
+        Ok(())
+    }
+
+    pub fn reset(&mut self) {
+        self.previous = None;
+        self.jumps = 0;
+    }
+
+    fn check_ranges(
+        limits: &ValidationLimits,
+        frame: &Bno08xRvcRawFrame,
+    ) -> Result<(), InvalidReason> {
+        if !(-18_000..=18_000).contains(&frame.yaw) {
+            return Err(InvalidReason::YawOutOfRange);
+        }
+        if !(-9_000..=9_000).contains(&frame.pitch) {
+            return Err(InvalidReason::PitchOutOfRange);
+        }
+        if !(-18_000..=18_000).contains(&frame.roll) {
+            return Err(InvalidReason::RollOutOfRange);
+        }
+        let max = limits.max_abs_acc_milli_g as i32;
+        if [frame.x_acc, frame.y_acc, frame.z_acc]
+            .iter()
+            .any(|a| (*a as i32).abs() > max)
+        {
+            return Err(InvalidReason::AccelerationOutOfRange);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use crate::parser::Bno08xRvcPrettyFrame;
+use crate::units::G_ACCELERATION;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct VibrationConfig {
+    /// Single-sample dynamic acceleration (m/s2) that counts as a shock.
+    pub shock_threshold: f32,
+    /// Window RMS of dynamic acceleration (m/s2) that counts as vibration.
+    pub vibration_threshold: f32,
+}
+
+/// Statistics of the dynamic acceleration `|a| - g` over the window.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VibrationStats {
+    pub rms: f32,          // m/s2
+    pub peak: f32,         // m/s2, largest absolute value
+    pub crest_factor: f32, // peak / rms, 0 while rms is 0
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VibrationEventKind {
+    Shock,
+    Vibration,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VibrationEvent {
+    pub kind: VibrationEventKind,
+    pub index: u8, // index of the frame that crossed the threshold
+    pub peak: f32, // m/s2, sample value for shocks, window peak for vibration
+    pub rms: f32,  // m/s2, window RMS at the crossing
+}
+
+/// Events raised by one frame. A shock and a vibration crossing can
+/// happen on the same frame.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct VibrationEvents {
+    pub shock: Option<VibrationEvent>,
+    pub vibration: Option<VibrationEvent>,
+}
+
+impl VibrationEvents {
+    pub fn is_empty(&self) -> bool {
+        self.shock.is_none() && self.vibration.is_none()
+    }
+
+    /// The shock first, then the vibration.
+    pub fn iter(&self) -> impl Iterator<Item = VibrationEvent> {
+        self.shock.into_iter().chain(self.vibration)
+    }
+}
+
+/// Rolling RMS / peak / crest factor of the accel magnitude over the last
+/// `N` frames, with gravity removed.
+///
+/// Each threshold emits one event when it is crossed upwards and re-arms
+/// once the signal drops back below it.
+pub struct VibrationMonitor<const N: usize> {
+    config: VibrationConfig,
+    samples: [f32; N],
+    len: usize,
+    pos: usize,
+    in_shock: bool,
+    in_vibration: bool,
+    stats: VibrationStats,
+}
+
+impl<const N: usize> VibrationMonitor<N> {
+    pub fn new(config: VibrationConfig) -> Self {
+        VibrationMonitor {
+            config,
+            samples: [0.0; N],
+            len: 0,
+            pos: 0,
+            in_shock: false,
+            in_vibration: false,
+            stats: VibrationStats::default(),
+        }
+    }
+
+    pub fn update(&mut self, frame: &Bno08xRvcPrettyFrame) -> VibrationEvents {
+        let mut events = VibrationEvents::default();
+        if N == 0 {
+            return events;
+        }
+        let magnitude = libm::sqrtf(
+            frame.x_acc * frame.x_acc + frame.y_acc * frame.y_acc + frame.z_acc * frame.z_acc,
+        );
+        let sample = magnitude - G_ACCELERATION;
+        self.samples[self.pos] = sample;
+        self.pos = (self.pos + 1) % N;
+        if self.len < N {
+            self.len += 1;
+        }
+
+        let window = &self.samples[..self.len];
+        let rms = libm::sqrtf(window.iter().map(|s| s * s).sum::<f32>() / self.len as f32);
+        let peak = window.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        self.stats = VibrationStats {
+            rms,
+            peak,
+            crest_factor: if rms > 0.0 { peak / rms } else { 0.0 },
+        };
+
+        let shock = sample.abs() > self.config.shock_threshold;
+        if shock && !self.in_shock {
+            events.shock = Some(VibrationEvent {
+                kind: VibrationEventKind::Shock,
+                index: frame.index,
+                peak: sample.abs(),
+                rms,
+            });
+        }
+        self.in_shock = shock;
+
+        let vibration = rms > self.config.vibration_threshold;
+        if vibration && !self.in_vibration {
+            events.vibration = Some(VibrationEvent {
+                kind: VibrationEventKind::Vibration,
+                index: frame.index,
+                peak,
+                rms,
+            });
+        }
+        self.in_vibration = vibration;
+        events
+    }
+
+    pub fn stats(&self) -> VibrationStats {
+        self.stats
+    }
+
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.pos = 0;
+        self.in_shock = false;
+        self.in_vibration = false;
+        self.stats = VibrationStats::default();
+    }
+}